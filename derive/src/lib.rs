@@ -0,0 +1,291 @@
+//! `#[derive(Cast)]` for `math_traits`, use it through the `derive` feature of `math_traits`.
+//!
+//! The derived impl converts every field on its own, like the impl for tuples.
+//! Each type parameter `T` of the struct gets its own target parameter,
+//! so `Rgb<f32>` casts to `Rgb<u8>` if every field does.
+//! Ranges for `cast_clipped` and `cast_clamped` are given per field.
+//!
+//! There are no dependencies, so the input is parsed by hand. Structs with
+//! named fields, tuple structs and unit structs are supported, without where clauses.
+extern crate proc_macro;
+
+use proc_macro::{Delimiter, Group, Ident, Span, TokenStream, TokenTree};
+use std::iter::FromIterator;
+
+#[proc_macro_derive(Cast)]
+pub fn derive_cast(input: TokenStream) -> TokenStream {
+    let code = match parse(input) {
+        Ok(s) => generate(&s),
+        Err(e) => format!("compile_error!({:?});", e),
+    };
+    code.parse().unwrap()
+}
+
+enum Param {
+    Lifetime(String),
+    // declaration, name
+    Const(String, String),
+    // name, bounds
+    Type(String, TokenStream),
+}
+
+enum Fields {
+    Named(Vec<(String, TokenStream)>),
+    Unnamed(Vec<TokenStream>),
+    Unit,
+}
+
+struct Struct {
+    name: String,
+    params: Vec<Param>,
+    fields: Fields,
+}
+
+fn is_punct(t: Option<&TokenTree>, c: char) -> bool {
+    match t {
+        Some(&TokenTree::Punct(ref p)) => p.as_char() == c,
+        _ => false
+    }
+}
+
+fn is_ident(t: Option<&TokenTree>, s: &str) -> bool {
+    match t {
+        Some(&TokenTree::Ident(ref i)) => i.to_string() == s,
+        _ => false
+    }
+}
+
+/// skip attributes and the visibility
+fn skip_prefix(tokens: &[TokenTree]) -> &[TokenTree] {
+    let mut t = tokens;
+    loop {
+        if is_punct(t.get(0), '#') {
+            t = &t[2..];
+        } else if is_ident(t.get(0), "pub") {
+            match t.get(1) {
+                Some(&TokenTree::Group(ref g)) if g.delimiter() == Delimiter::Parenthesis => t = &t[2..],
+                _ => t = &t[1..]
+            }
+        } else {
+            return t;
+        }
+    }
+}
+
+/// split at commas outside of angle brackets
+fn split_commas(tokens: &[TokenTree]) -> Vec<&[TokenTree]> {
+    let mut parts = vec![];
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, t) in tokens.iter().enumerate() {
+        if let TokenTree::Punct(ref p) = *t {
+            match p.as_char() {
+                '<' => depth += 1,
+                '>' if depth > 0 => depth -= 1,
+                ',' if depth == 0 => {
+                    parts.push(&tokens[start .. i]);
+                    start = i + 1;
+                }
+                _ => {}
+            }
+        }
+    }
+    if start < tokens.len() {
+        parts.push(&tokens[start ..]);
+    }
+    parts
+}
+
+fn stream(tokens: &[TokenTree]) -> TokenStream {
+    TokenStream::from_iter(tokens.iter().cloned())
+}
+
+fn parse(input: TokenStream) -> Result<Struct, String> {
+    let tokens: Vec<TokenTree> = input.into_iter().collect();
+    let mut t = skip_prefix(&tokens);
+
+    if !is_ident(t.get(0), "struct") {
+        return Err("#[derive(Cast)] only supports structs".into());
+    }
+    let name = match t.get(1) {
+        Some(&TokenTree::Ident(ref i)) => i.to_string(),
+        _ => return Err("expected the name of the struct".into())
+    };
+    t = &t[2..];
+
+    let mut params = vec![];
+    if is_punct(t.get(0), '<') {
+        let mut depth = 0;
+        let end = t.iter().position(|t| {
+            match *t {
+                TokenTree::Punct(ref p) if p.as_char() == '<' => depth += 1,
+                TokenTree::Punct(ref p) if p.as_char() == '>' => depth -= 1,
+                _ => {}
+            }
+            depth == 0
+        }).ok_or("unterminated generics")?;
+
+        for p in split_commas(&t[1 .. end]) {
+            // drop defaults
+            let p = match p.iter().position(|t| is_punct(Some(t), '=')) {
+                Some(i) => &p[.. i],
+                None => p
+            };
+            if is_punct(p.get(0), '\'') {
+                params.push(Param::Lifetime(stream(p).to_string()));
+            } else if is_ident(p.get(0), "const") {
+                params.push(Param::Const(stream(p).to_string(), p[1].to_string()));
+            } else {
+                let bounds = if is_punct(p.get(1), ':') { stream(&p[2..]) } else { TokenStream::new() };
+                params.push(Param::Type(p[0].to_string(), bounds));
+            }
+        }
+        t = &t[end + 1 ..];
+    }
+
+    if is_ident(t.get(0), "where") {
+        return Err("#[derive(Cast)] does not support where clauses, put the bounds on the parameters".into());
+    }
+
+    let fields = match t.get(0) {
+        Some(&TokenTree::Group(ref g)) if g.delimiter() == Delimiter::Brace => {
+            let body: Vec<TokenTree> = g.stream().into_iter().collect();
+            let mut fields = vec![];
+            for f in split_commas(&body) {
+                let f = skip_prefix(f);
+                if f.len() < 3 || !is_punct(f.get(1), ':') {
+                    return Err("expected a field".into());
+                }
+                fields.push((f[0].to_string(), stream(&f[2..])));
+            }
+            Fields::Named(fields)
+        }
+        Some(&TokenTree::Group(ref g)) if g.delimiter() == Delimiter::Parenthesis => {
+            let body: Vec<TokenTree> = g.stream().into_iter().collect();
+            Fields::Unnamed(split_commas(&body).into_iter().map(|f| stream(skip_prefix(f))).collect())
+        }
+        _ => Fields::Unit
+    };
+
+    Ok(Struct { name, params, fields })
+}
+
+fn target_name(param: &str) -> String {
+    format!("__{}_out", param)
+}
+
+/// replace every type parameter by its target parameter
+fn substitute(ts: TokenStream, params: &[Param]) -> TokenStream {
+    ts.into_iter().map(|t| match t {
+        TokenTree::Ident(ref i) => {
+            let name = i.to_string();
+            if params.iter().any(|p| match *p { Param::Type(ref n, _) => *n == name, _ => false }) {
+                TokenTree::Ident(Ident::new(&target_name(&name), Span::call_site()))
+            } else {
+                t.clone()
+            }
+        }
+        TokenTree::Group(ref g) => {
+            let mut n = Group::new(g.delimiter(), substitute(g.stream(), params));
+            n.set_span(g.span());
+            TokenTree::Group(n)
+        }
+        t => t
+    }).collect()
+}
+
+fn generate(s: &Struct) -> String {
+    let mut decl = vec![];
+    let mut src_args = vec![];
+    let mut dst_args = vec![];
+    for p in &s.params {
+        match *p {
+            Param::Lifetime(ref l) => {
+                decl.push(l.clone());
+                src_args.push(l.split(':').next().unwrap().trim().to_string());
+                dst_args.push(src_args.last().unwrap().clone());
+            }
+            Param::Const(ref d, ref n) => {
+                decl.push(d.clone());
+                src_args.push(n.clone());
+                dst_args.push(n.clone());
+            }
+            Param::Type(ref n, ref bounds) => {
+                let out = target_name(n);
+                if bounds.is_empty() {
+                    decl.push(n.clone());
+                    decl.push(out.clone());
+                } else {
+                    decl.push(format!("{}: {}", n, bounds));
+                    decl.push(format!("{}: {}", out, substitute(bounds.clone(), &s.params)));
+                }
+                src_args.push(n.clone());
+                dst_args.push(out);
+            }
+        }
+    }
+    let name = &s.name;
+    let src = format!("{}<{}>", name, src_args.join(", "));
+    let dst = format!("{}<{}>", name, dst_args.join(", "));
+
+    // (access, source type, target type)
+    let fields: Vec<(String, String, String)> = match s.fields {
+        Fields::Named(ref f) => f.iter().map(|&(ref n, ref ty)|
+            (n.clone(), ty.to_string(), substitute(ty.clone(), &s.params).to_string())
+        ).collect(),
+        Fields::Unnamed(ref f) => f.iter().enumerate().map(|(i, ty)|
+            (i.to_string(), ty.to_string(), substitute(ty.clone(), &s.params).to_string())
+        ).collect(),
+        Fields::Unit => vec![]
+    };
+    let bounds: Vec<String> = fields.iter()
+        .map(|&(_, ref a, ref b)| format!("{}: ::math_traits::Cast<{}>", a, b))
+        .collect();
+
+    // build the value from one expression per field
+    let construct = |f: &dyn Fn(&str, &str, &str) -> String| -> String {
+        let values: Vec<String> = fields.iter().map(|&(ref n, ref a, ref b)| f(n, a, b)).collect();
+        match s.fields {
+            Fields::Named(_) => format!("{} {{ {} }}", name,
+                fields.iter().zip(&values).map(|(f, v)| format!("{}: {}", f.0, v)).collect::<Vec<_>>().join(", ")),
+            Fields::Unnamed(_) => format!("{}({})", name, values.join(", ")),
+            Fields::Unit => name.clone()
+        }
+    };
+    let call = |method: &str, a: &str, b: &str, args: &str| -> String {
+        format!("<{} as ::math_traits::Cast<{}>>::{}({})", a, b, method, args)
+    };
+
+    let cast = construct(&|n, a, b| format!("{}?", call("cast", a, b, &format!("self.{}", n))));
+    let clipped = construct(&|n, a, b| format!("{}?", call("cast_clipped", a, b,
+        &format!("self.{0}, start.{0} ..= end.{0}", n))));
+    let clamped = construct(&|n, a, b| call("cast_clamped", a, b,
+        &format!("self.{0}, start.{0} ..= end.{0}", n)));
+    let clamping = construct(&|n, a, b| call("cast_clamping", a, b, &format!("self.{}", n)));
+
+    format!("
+        #[allow(non_camel_case_types, unused_variables)]
+        impl<{decl}> ::math_traits::Cast<{dst}> for {src} where {bounds} {{
+            #[inline]
+            fn cast(self) -> ::std::option::Option<{dst}> {{
+                ::std::option::Option::Some({cast})
+            }}
+            #[inline]
+            fn cast_clipped(self, r: ::std::ops::RangeInclusive<{dst}>) -> ::std::option::Option<{dst}> {{
+                let (start, end) = r.into_inner();
+                ::std::option::Option::Some({clipped})
+            }}
+            #[inline]
+            fn cast_clamped(self, r: ::std::ops::RangeInclusive<{dst}>) -> {dst} {{
+                let (start, end) = r.into_inner();
+                {clamped}
+            }}
+            #[inline]
+            fn cast_clamping(self) -> {dst} {{
+                {clamping}
+            }}
+        }}",
+        decl = decl.join(", "), src = src, dst = dst, bounds = bounds.join(", "),
+        cast = cast, clipped = clipped, clamped = clamped, clamping = clamping
+    )
+}
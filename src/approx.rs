@@ -0,0 +1,400 @@
+// Branch-free polynomial approximations used by the vector implementations.
+// Everything here is expressed in terms of `Real`, so the same code serves
+// every lane width. Only the exponent manipulation needs to know the bit
+// layout, which the backends provide through `Exponent`.
+use real::Real;
+use mask::Mask;
+
+pub trait Exponent: Real {
+    /// largest |n| for which `ldexp` does not saturate
+    const LDEXP_MAX: i16;
+
+    /// self * 2^n for integral n
+    fn ldexp(self, n: Self) -> Self;
+
+    /// split a positive finite self into (m, e) with self = m * 2^e and 1 <= m < 2
+    fn frexp(self) -> (Self, Self);
+}
+
+// pi/2 split into three parts, the first two exactly representable in f32,
+// so that x - j * pi/2 does not lose precision for moderate j.
+const PIO2_1: f64 = 1.5703125;
+const PIO2_2: f64 = 4.837512969970703e-4;
+const PIO2_3: f64 = 7.549789954891882e-8;
+
+// ln(2) split the same way
+const LN2_HI: f64 = 6.93145751953125e-1;
+const LN2_LO: f64 = 1.4286068203094173e-6;
+
+#[inline(always)]
+fn horner<R: Real>(x: R, coeffs: &[f64]) -> R {
+    let mut acc = R::float(coeffs[0]);
+    for &c in &coeffs[1..] {
+        acc = acc.mul_add(x, R::float(c));
+    }
+    acc
+}
+
+/// r - r^3/3! + ... on [-pi/4, pi/4]
+#[inline(always)]
+fn sin_kernel<R: Real>(r: R) -> R {
+    let z = r * r;
+    let p = horner(z, &[
+         1.58969099521155e-10,
+        -2.5050760253406863e-08,
+         2.7557313707070068e-06,
+        -1.984126982985795e-04,
+         8.33333333332249e-03,
+        -1.6666666666666632e-01,
+    ]);
+    (r * z).mul_add(p, r)
+}
+
+/// 1 - r^2/2! + ... on [-pi/4, pi/4]
+#[inline(always)]
+fn cos_kernel<R: Real>(r: R) -> R {
+    let z = r * r;
+    let p = horner(z, &[
+        -1.1359647557788195e-11,
+         2.087572321298175e-09,
+        -2.7557314351390663e-07,
+         2.480158728947673e-05,
+        -1.388888888887411e-03,
+         4.16666666666666e-02,
+    ]);
+    (z * z).mul_add(p, R::int(1) - R::frac(1, 2) * z)
+}
+
+/// sin(r + q * pi/2) for integral q
+#[inline(always)]
+fn sin_quadrant<R: Real>(r: R, q: R) -> R {
+    let zero = R::int(0);
+    let q = q - R::int(4) * (q * R::frac(1, 4)).floor();
+    let odd = (q - R::int(2) * (q * R::frac(1, 2)).floor()).eq(R::int(1));
+    let v = cos_kernel(r).select(sin_kernel(r), odd);
+    (zero - v).select(v, q.ge(R::int(2)))
+}
+
+/// x = r + j * pi/2 with |r| <= pi/4
+#[inline(always)]
+fn reduce_pio2<R: Real>(x: R) -> (R, R) {
+    let j = (x * R::float(::std::f64::consts::FRAC_2_PI) + R::frac(1, 2)).floor();
+    let r = j.mul_add(R::float(-PIO2_1), x);
+    let r = j.mul_add(R::float(-PIO2_2), r);
+    let r = j.mul_add(R::float(-PIO2_3), r);
+    (r, j)
+}
+
+#[inline(always)]
+pub fn sin<R: Real>(x: R) -> R {
+    let (r, j) = reduce_pio2(x);
+    sin_quadrant(r, j)
+}
+
+#[inline(always)]
+pub fn cos<R: Real>(x: R) -> R {
+    let (r, j) = reduce_pio2(x);
+    sin_quadrant(r, j + R::int(1))
+}
+
+/// e^r for |r| <= ln(2)/2
+#[inline(always)]
+fn exp_kernel<R: Real>(r: R) -> R {
+    // taylor series
+    horner(r, &[
+        1.0 / 6227020800.0,
+        1.0 / 479001600.0,
+        1.0 / 39916800.0,
+        1.0 / 3628800.0,
+        1.0 / 362880.0,
+        1.0 / 40320.0,
+        1.0 / 5040.0,
+        1.0 / 720.0,
+        1.0 / 120.0,
+        1.0 / 24.0,
+        1.0 / 6.0,
+        1.0 / 2.0,
+        1.0,
+        1.0,
+    ])
+}
+
+/// clamp x to the range where ldexp does not saturate. results outside saturate to 0 or inf anyway
+#[inline(always)]
+fn clamp_exponent<R: Exponent>(x: R, scale: f64) -> R {
+    let limit = R::float(R::LDEXP_MAX as f64 * scale);
+    x.clamp(R::int(0) - limit, limit)
+}
+
+#[inline(always)]
+pub fn exp<R: Exponent>(x: R) -> R {
+    let x = clamp_exponent(x, ::std::f64::consts::LN_2);
+    let n = (x * R::float(::std::f64::consts::LOG2_E) + R::frac(1, 2)).floor();
+    let r = n.mul_add(R::float(-LN2_HI), x);
+    let r = n.mul_add(R::float(-LN2_LO), r);
+    exp_kernel(r).ldexp(n)
+}
+
+#[inline(always)]
+pub fn exp2<R: Exponent>(x: R) -> R {
+    let x = clamp_exponent(x, 1.0);
+    let n = (x + R::frac(1, 2)).floor();
+    let r = (x - n) * R::float(::std::f64::consts::LN_2);
+    exp_kernel(r).ldexp(n)
+}
+
+/// split x into (e, ln(m)) with x = m * 2^e
+#[inline(always)]
+fn log_parts<R: Exponent>(x: R) -> (R, R) {
+    let one = R::int(1);
+    let (m, e) = x.frexp();
+
+    // move m into [sqrt(1/2), sqrt(2))
+    let sqrt2 = R::float(::std::f64::consts::SQRT_2);
+    let big = m.gt(sqrt2);
+    let e = (e + one).select(e, big);
+    let m = (m * R::frac(1, 2)).select(m, big);
+
+    // ln(m) = 2 atanh(s) with s = (m - 1) / (m + 1)
+    let f = m - one;
+    let s = f / (f + R::int(2));
+    let z = s * s;
+    let p = horner(z, &[
+        2.0 / 21.0,
+        2.0 / 19.0,
+        2.0 / 17.0,
+        2.0 / 15.0,
+        2.0 / 13.0,
+        2.0 / 11.0,
+        2.0 / 9.0,
+        2.0 / 7.0,
+        2.0 / 5.0,
+        2.0 / 3.0,
+        2.0,
+    ]);
+    (e, s * p)
+}
+
+/// fill in the results for x <= 0, inf and NaN
+#[inline(always)]
+fn log_special<R: Real>(x: R, v: R) -> R {
+    let zero = R::int(0);
+    let inf = R::INFINITY;
+    let nan = R::NAN;
+    let v = inf.select(v, x.eq(inf));
+    let v = R::NEG_INFINITY.select(v, x.eq(zero));
+    let v = nan.select(v, x.lt(zero));
+    // NaN input
+    v.select(x, x.eq(x))
+}
+
+#[inline(always)]
+pub fn ln<R: Exponent>(x: R) -> R {
+    let (e, lnm) = log_parts(x);
+    let v = e.mul_add(R::float(LN2_HI), e.mul_add(R::float(LN2_LO), lnm));
+    log_special(x, v)
+}
+
+#[inline(always)]
+pub fn log2<R: Exponent>(x: R) -> R {
+    let (e, lnm) = log_parts(x);
+    let v = lnm.mul_add(R::float(::std::f64::consts::LOG2_E), e);
+    log_special(x, v)
+}
+
+/// x^y = 2^(y log2 |x|), with the sign fixed up for negative x and integral y
+#[inline(always)]
+pub fn powf<R: Exponent>(x: R, y: R) -> R {
+    let zero = R::int(0);
+    let one = R::int(1);
+    let v = exp2(y * log2(x.abs()));
+
+    let integral = y.floor().eq(y);
+    let odd = (y - R::int(2) * (y * R::frac(1, 2)).floor()).eq(one);
+    // odd powers keep the sign, also of -0 and -inf
+    let v = v.copysign(x).select(v, odd);
+    // finite negative x with fractional y has no real result
+    let v = R::NAN.select(v, x.lt(zero).and(x.is_finite()).and(integral.not()));
+
+    // x^0 = 1 and 1^y = 1, even for NaN, and (-1)^inf = 1
+    let v = one.select(v, y.eq(zero));
+    let v = one.select(v, x.eq(R::int(-1)).and(y.is_infinite()));
+    one.select(v, x.eq(one))
+}
+
+// compare every lane against the scalar implementation
+#[cfg(test)]
+pub fn check_lanes<R>(eps: f64) where R: Real, R::Scalar: Real<Bool=bool> + Into<f64> + PartialEq {
+    let input: Vec<R::Scalar> = (0 .. 64 * R::LANES)
+        .map(|i| <R::Scalar as Real>::float(i as f64 * 0.37 - 20.))
+        .collect();
+
+    macro_rules! compare {
+        ($($f:ident),*) => ( $(
+            for c in input.chunks(R::LANES) {
+                let v = R::$f(R::from_slice(c));
+                for (a, &x) in v.values().zip(c) {
+                    let (a, b): (f64, f64) = (a.into(), <R::Scalar as Real>::$f(x).into());
+                    assert!(a == b || (a - b).abs() <= eps * b.abs().max(1.) || (a.is_nan() && b.is_nan()),
+                        "{}({:?}) = {}, expected {}", stringify!($f), x, a, b);
+                }
+            }
+        )* )
+    }
+    compare!(abs, sqrt, floor, ceil, sin, cos, exp, ln, exp2, log2);
+
+    for c in input.chunks(R::LANES) {
+        let v = R::from_slice(c);
+        let sum: f64 = c.iter().map(|&x| x.into()).sum();
+        let reduced: f64 = v.reduce_sum().into();
+        assert!((reduced - sum).abs() <= eps * sum.abs().max(1.));
+
+        let min = c.iter().cloned().fold(c[0], |a, b| a.min(b));
+        assert!(v.reduce_min() == min);
+        
+        let sel = v.select(R::int(0), v.gt(R::int(0)));
+        assert!(sel.values().zip(c).all(|(a, &x)| a == x.max(<R::Scalar as Real>::int(0))));
+    }
+
+    // exact results on special values, including the sign of zeros
+    let f = |x: f64| <R::Scalar as Real>::float(x);
+    let (zero, one) = (f(0.), f(1.));
+    let specials = [
+        <R::Scalar as Real>::NAN, <R::Scalar as Real>::INFINITY, <R::Scalar as Real>::NEG_INFINITY,
+        zero, zero * (zero - one), f(0.5), f(-0.5), f(1.5), f(-1.5),
+        f(2.5), f(-2.5), f(0.49999997), f(-3.7), f(1e7 + 0.5), f(1e30), f(-1e-30),
+    ];
+    let specials: Vec<R::Scalar> = specials.iter().cloned().cycle().take(specials.len() * R::LANES).collect();
+    let same = |a: f64, b: f64| a.to_bits() == b.to_bits() || (a.is_nan() && b.is_nan());
+
+    macro_rules! exact {
+        ($($f:ident),*) => ( $(
+            for c in specials.chunks(R::LANES) {
+                let v = R::$f(R::from_slice(c));
+                for (a, &x) in v.values().zip(c) {
+                    let (a, b): (f64, f64) = (a.into(), <R::Scalar as Real>::$f(x).into());
+                    assert!(same(a, b), "{}({:?}) = {}, expected {}", stringify!($f), x, a, b);
+                }
+            }
+        )* )
+    }
+    exact!(abs, floor, ceil, round, trunc, fract, signum);
+
+    macro_rules! classify {
+        ($($f:ident),*) => ( $(
+            for c in specials.chunks(R::LANES) {
+                let m = R::int(1).select(R::int(0), R::$f(R::from_slice(c)));
+                for (a, &x) in m.values().zip(c) {
+                    let a: f64 = a.into();
+                    assert_eq!(a == 1., <R::Scalar as Real>::$f(x), "{}({:?})", stringify!($f), x);
+                }
+            }
+        )* )
+    }
+    classify!(is_nan, is_finite, is_infinite, is_sign_negative);
+
+    for (c, d) in specials.chunks(R::LANES).zip(specials.chunks(R::LANES).rev()) {
+        let v = R::from_slice(c).copysign(R::from_slice(d));
+        for ((a, &x), &y) in v.values().zip(c).zip(d) {
+            let (a, b): (f64, f64) = (a.into(), x.copysign(y).into());
+            assert!(same(a, b), "copysign({:?}, {:?}) = {}, expected {}", x, y, a, b);
+        }
+    }
+
+    macro_rules! consts {
+        ($($c:ident),*) => ( $(
+            assert!(R::$c.values().all(|a| same(a.into(), <R::Scalar as Real>::$c.into())), stringify!($c));
+        )* )
+    }
+    consts!(PI, E, TAU, FRAC_PI_2, LN_2, LN_10, SQRT_2, EPSILON, INFINITY, NEG_INFINITY, NAN, MIN_POSITIVE, MAX, MIN);
+}
+
+// scalar `Exponent`, so the approximations can be checked without a vector backend
+#[cfg(test)]
+macro_rules! impl_exponent_scalar {
+    ($($t:ident: $bits:ident),*) => ( $(
+        impl Exponent for $t {
+            const LDEXP_MAX: i16 = 2 * ($t::MAX_EXP as i16 - 2);
+
+            fn ldexp(self, n: Self) -> Self {
+                let n = if n == n { n.max(-(Self::LDEXP_MAX as $t)).min(Self::LDEXP_MAX as $t) } else { 0. };
+                let n1 = (n * 0.5).floor();
+                self * (2 as $t).powi(n1 as i32) * (2 as $t).powi((n - n1) as i32)
+            }
+
+            fn frexp(self) -> (Self, Self) {
+                let mant = $t::MANTISSA_DIGITS - 1;
+                let bias = $t::MAX_EXP as $bits - 1;
+                let (x, offset) = if self < $t::MIN_POSITIVE {
+                    (self * ((1 as $bits) << mant) as $t, bias + mant as $bits)
+                } else {
+                    (self, bias)
+                };
+                let bits = x.to_bits();
+                let e = ((bits >> mant) & (2 * bias + 1)) as $t - offset as $t;
+                let m = $t::from_bits((bits & (((1 as $bits) << mant) - 1)) | (bias << mant));
+                (m, e)
+            }
+        }
+    )* )
+}
+#[cfg(test)]
+impl_exponent_scalar!(f32: u32, f64: u64);
+
+#[cfg(test)]
+fn check_accuracy<R>(eps: f64) where R: Exponent<Bool=bool> + Into<f64> {
+    let close = |f: &str, x: R, a: R, b: f64| {
+        let a: f64 = a.into();
+        assert!(a == b || (a - b).abs() <= eps * b.abs().max(1.) || (a.is_nan() && b.is_nan()),
+            "{}({:?}) = {}, expected {}", f, x, a, b);
+    };
+    for i in -20000 .. 20000 {
+        let x = R::float(i as f64 * 0.005);
+        let v: f64 = x.into();
+        close("sin", x, sin(x), v.sin());
+        close("cos", x, cos(x), v.cos());
+    }
+    for i in -2000 .. 2000 {
+        let x = R::float(i as f64 * 0.37);
+        let v: f64 = x.into();
+        close("exp", x, exp(x), R::float(v.exp()).into());
+        close("exp2", x, exp2(x), R::float(v.exp2()).into());
+    }
+    // positive values over the whole range, including subnormals
+    let mut x = R::MIN_POSITIVE * R::float(1e-5);
+    while x.lt(R::MAX) {
+        let v: f64 = x.into();
+        close("ln", x, ln(x), v.ln());
+        close("log2", x, log2(x), v.log2());
+        x = x * R::float(1.37);
+    }
+    for &x in &[R::int(0), R::int(-1), R::INFINITY, R::NEG_INFINITY, R::NAN] {
+        let v: f64 = x.into();
+        close("ln", x, ln(x), v.ln());
+        close("exp", x, exp(x), v.exp());
+        close("sin", x, sin(x), v.sin());
+    }
+    let specials = [0., -0., 1., -1., 2., -2., 0.5, -0.5, 3., -3., 2.5, -2.5, 1e30, -1e30,
+        f64::INFINITY, f64::NEG_INFINITY, f64::NAN];
+    for &a in &specials {
+        for &b in &specials {
+            let (x, y) = (R::float(a), R::float(b));
+            let p = powf(x, y);
+            let (v, e): (f64, f64) = (p.into(), R::float(a.powf(b)).into());
+            // the error of exp2(y log2 x) grows with the size of the exponent
+            let tol = eps * e.abs() * e.abs().ln().abs().max(1.);
+            assert!(v.to_bits() == e.to_bits() || (v - e).abs() <= tol || (v.is_nan() && e.is_nan()),
+                "powf({}, {}) = {}, expected {}", a, b, v, e);
+        }
+    }
+}
+
+#[test]
+fn test_accuracy_f32() {
+    check_accuracy::<f32>(1e-6);
+}
+
+#[test]
+fn test_accuracy_f64() {
+    check_accuracy::<f64>(1e-14);
+}
@@ -0,0 +1,305 @@
+//! `Real` for arrays.
+//!
+//! The arithmetic operators can not be implemented for `[T; N]` outside of std,
+//! so `Real` is implemented for the wrapper `Array<T, N>` instead.
+//! The lanes of all elements are flattened: `Array<f32x4, 2>` has 8 lanes of f32.
+//! `Array` converts from and into `[T; N]` and derefs to it, so the array
+//! methods remain available.
+use std::ops::{Add, Sub, Mul, Div, Deref, DerefMut};
+use std::array::from_fn;
+use rand::Rng;
+use real::Real;
+
+/// `[T; N]` with lane-wise arithmetic.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Array<T, const N: usize>(pub [T; N]);
+
+impl<T, const N: usize> From<[T; N]> for Array<T, N> {
+    #[inline(always)]
+    fn from(a: [T; N]) -> Self {
+        Array(a)
+    }
+}
+impl<T, const N: usize> From<Array<T, N>> for [T; N] {
+    #[inline(always)]
+    fn from(a: Array<T, N>) -> Self {
+        a.0
+    }
+}
+impl<T, const N: usize> Deref for Array<T, N> {
+    type Target = [T; N];
+    #[inline(always)]
+    fn deref(&self) -> &[T; N] {
+        &self.0
+    }
+}
+impl<T, const N: usize> DerefMut for Array<T, N> {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut [T; N] {
+        &mut self.0
+    }
+}
+impl<T, const N: usize> AsRef<[T; N]> for Array<T, N> {
+    #[inline(always)]
+    fn as_ref(&self) -> &[T; N] {
+        &self.0
+    }
+}
+impl<T, const N: usize> AsMut<[T; N]> for Array<T, N> {
+    #[inline(always)]
+    fn as_mut(&mut self) -> &mut [T; N] {
+        &mut self.0
+    }
+}
+
+macro_rules! impl_op {
+    ($($Op:ident, $op:ident;)*) => ($(
+        impl<T: Real, const N: usize> $Op for Array<T, N> {
+            type Output = Self;
+            #[inline]
+            fn $op(self, rhs: Self) -> Self {
+                Array(from_fn(|i| T::$op(self.0[i], rhs.0[i])))
+            }
+        }
+    )*)
+}
+impl_op!(Add, add; Sub, sub; Mul, mul; Div, div;);
+
+// element-wise versions of the methods of Real
+macro_rules! map {
+    ($self:ident, $f:ident $(, $arg:ident)*) => (
+        Array(from_fn(|i| T::$f($self.0[i] $(, $arg.0[i])*)))
+    )
+}
+// combine the elements with f, `empty` is the identity used for N = 0
+#[inline]
+fn fold<T: Copy>(a: &[T], empty: T, f: impl Fn(T, T) -> T) -> T {
+    match a.split_first() {
+        Some((&first, rest)) => rest.iter().fold(first, |acc, &x| f(acc, x)),
+        None => empty
+    }
+}
+macro_rules! splat {
+    ($v:expr) => ({
+        let v = $v;
+        Array([v; N])
+    })
+}
+
+impl<T: Real, const N: usize> Real for Array<T, N> {
+    const PI: Self = Array([T::PI; N]);
+    const E: Self = Array([T::E; N]);
+    const TAU: Self = Array([T::TAU; N]);
+    const FRAC_PI_2: Self = Array([T::FRAC_PI_2; N]);
+    const LN_2: Self = Array([T::LN_2; N]);
+    const LN_10: Self = Array([T::LN_10; N]);
+    const SQRT_2: Self = Array([T::SQRT_2; N]);
+    const EPSILON: Self = Array([T::EPSILON; N]);
+    const INFINITY: Self = Array([T::INFINITY; N]);
+    const NEG_INFINITY: Self = Array([T::NEG_INFINITY; N]);
+    const NAN: Self = Array([T::NAN; N]);
+    const MIN_POSITIVE: Self = Array([T::MIN_POSITIVE; N]);
+    const MAX: Self = Array([T::MAX; N]);
+    const MIN: Self = Array([T::MIN; N]);
+    const LANES: usize = N * T::LANES;
+    type Bool = [T::Bool; N];
+    type Scalar = T::Scalar;
+    type Iterator = ::std::iter::FlatMap<::std::array::IntoIter<T, N>, T::Iterator, fn(T) -> T::Iterator>;
+
+    #[inline]
+    fn splat(s: Self::Scalar) -> Self {
+        splat!(T::splat(s))
+    }
+    #[inline]
+    fn values(self) -> Self::Iterator {
+        IntoIterator::into_iter(self.0).flat_map(T::values as fn(T) -> T::Iterator)
+    }
+
+    #[inline]
+    fn from_slice(s: &[Self::Scalar]) -> Self {
+        Array(from_fn(|i| T::from_slice(&s[i * T::LANES ..])))
+    }
+    #[inline]
+    fn from_slice_padded(s: &[Self::Scalar], pad: Self::Scalar) -> Self {
+        Array(from_fn(|i| T::from_slice_padded(s.get(i * T::LANES ..).unwrap_or(&[]), pad)))
+    }
+    #[inline]
+    fn write_to_slice(self, s: &mut [Self::Scalar]) {
+        for (i, x) in self.0.iter().enumerate() {
+            x.write_to_slice(&mut s[i * T::LANES ..]);
+        }
+    }
+    #[inline]
+    fn write_to_slice_partial(self, s: &mut [Self::Scalar]) {
+        for (i, x) in self.0.iter().enumerate() {
+            if let Some(part) = s.get_mut(i * T::LANES ..) {
+                x.write_to_slice_partial(part);
+            }
+        }
+    }
+
+    #[inline]
+    fn reduce_sum(self) -> Self::Scalar {
+        fold(&self.0, T::int(0), |a, b| a + b).reduce_sum()
+    }
+    #[inline]
+    fn reduce_product(self) -> Self::Scalar {
+        fold(&self.0, T::int(1), |a, b| a * b).reduce_product()
+    }
+    #[inline]
+    fn reduce_min(self) -> Self::Scalar {
+        fold(&self.0, T::INFINITY, |a, b| T::min(a, b)).reduce_min()
+    }
+    #[inline]
+    fn reduce_max(self) -> Self::Scalar {
+        fold(&self.0, T::NEG_INFINITY, |a, b| T::max(a, b)).reduce_max()
+    }
+
+    #[inline]
+    fn int(v: i16) -> Self { splat!(T::int(v)) }
+    #[inline]
+    fn float(f: f64) -> Self { splat!(T::float(f)) }
+    #[inline]
+    fn try_float(f: f64) -> Option<Self> { Some(splat!(T::try_float(f)?)) }
+    #[inline]
+    fn float_saturating(f: f64) -> Self { splat!(T::float_saturating(f)) }
+    #[inline]
+    fn frac(nom: i16, denom: u16) -> Self { splat!(T::frac(nom, denom)) }
+    #[inline]
+    fn from_i32(v: i32) -> Self { splat!(T::from_i32(v)) }
+    #[inline]
+    fn from_i64(v: i64) -> Self { splat!(T::from_i64(v)) }
+    #[inline]
+    fn from_u32(v: u32) -> Self { splat!(T::from_u32(v)) }
+    #[inline]
+    fn from_ratio(nom: i64, denom: u64) -> Self { splat!(T::from_ratio(nom, denom)) }
+
+    #[inline]
+    fn uniform01<R: Rng>(rng: &mut R) -> Self {
+        Array(from_fn(|_| T::uniform01(rng)))
+    }
+
+    #[inline]
+    fn abs(self) -> Self { map!(self, abs) }
+    #[inline]
+    fn sqrt(self) -> Self { map!(self, sqrt) }
+    #[inline]
+    fn powf(self, e: Self) -> Self { map!(self, powf, e) }
+    #[inline]
+    fn powi(self, n: i32) -> Self { Array(from_fn(|i| T::powi(self.0[i], n))) }
+    #[inline]
+    fn exp2(self) -> Self { map!(self, exp2) }
+    #[inline]
+    fn log2(self) -> Self { map!(self, log2) }
+    #[inline]
+    fn sin(self) -> Self { map!(self, sin) }
+    #[inline]
+    fn cos(self) -> Self { map!(self, cos) }
+    #[inline]
+    fn exp(self) -> Self { map!(self, exp) }
+    #[inline]
+    fn ln(self) -> Self { map!(self, ln) }
+    #[inline]
+    fn floor(self) -> Self { map!(self, floor) }
+    #[inline]
+    fn ceil(self) -> Self { map!(self, ceil) }
+    #[inline]
+    fn round(self) -> Self { map!(self, round) }
+    #[inline]
+    fn trunc(self) -> Self { map!(self, trunc) }
+    #[inline]
+    fn fract(self) -> Self { map!(self, fract) }
+    #[inline]
+    fn signum(self) -> Self { map!(self, signum) }
+    #[inline]
+    fn copysign(self, sign: Self) -> Self { map!(self, copysign, sign) }
+    #[inline]
+    fn mul_add(self, b: Self, c: Self) -> Self { map!(self, mul_add, b, c) }
+    #[inline]
+    fn wrap(self, at: Self, span: Self) -> Self { map!(self, wrap, at, span) }
+    #[inline]
+    fn clamp(self, min: Self, max: Self) -> Self { map!(self, clamp, min, max) }
+    #[inline]
+    fn max(self, other: Self) -> Self { map!(self, max, other) }
+    #[inline]
+    fn min(self, other: Self) -> Self { map!(self, min, other) }
+
+    #[inline]
+    fn is_nan(self) -> Self::Bool { self.0.map(T::is_nan) }
+    #[inline]
+    fn is_finite(self) -> Self::Bool { self.0.map(T::is_finite) }
+    #[inline]
+    fn is_infinite(self) -> Self::Bool { self.0.map(T::is_infinite) }
+    #[inline]
+    fn is_sign_negative(self) -> Self::Bool { self.0.map(T::is_sign_negative) }
+
+    #[inline]
+    fn lt(self, rhs: Self) -> Self::Bool { from_fn(|i| T::lt(self.0[i], rhs.0[i])) }
+    #[inline]
+    fn le(self, rhs: Self) -> Self::Bool { from_fn(|i| T::le(self.0[i], rhs.0[i])) }
+    #[inline]
+    fn gt(self, rhs: Self) -> Self::Bool { from_fn(|i| T::gt(self.0[i], rhs.0[i])) }
+    #[inline]
+    fn ge(self, rhs: Self) -> Self::Bool { from_fn(|i| T::ge(self.0[i], rhs.0[i])) }
+    #[inline]
+    fn eq(self, rhs: Self) -> Self::Bool { from_fn(|i| T::eq(self.0[i], rhs.0[i])) }
+
+    #[inline]
+    fn select(self, other: Self, cond: Self::Bool) -> Self {
+        Array(from_fn(|i| T::select(self.0[i], other.0[i], cond[i])))
+    }
+}
+
+#[test]
+fn test_array() {
+    use mask::Mask;
+    let a = Array([3.0f32, -1.0, 4.0, 2.5]);
+    assert_eq!(Array::<f32, 4>::LANES, 4);
+    assert_eq!(a.reduce_sum(), 8.5);
+    assert_eq!(a.reduce_min(), -1.0);
+    assert_eq!(a.reduce_max(), 4.0);
+    assert_eq!(a * Array::splat(2.0), Array([6.0, -2.0, 8.0, 5.0]));
+    assert_eq!(a.gt(Array::int(2)), [true, false, true, true]);
+    assert_eq!(a.gt(Array::int(2)).count_true(), 3);
+    assert_eq!(a.clamp(Array::int(0), Array::int(3)), Array([3.0, 0.0, 3.0, 2.5]));
+    assert_eq!(a.select(Array::int(0), a.lt(Array::int(0)).not()), Array([3.0, 0.0, 4.0, 2.5]));
+    assert_eq!(a.round(), Array([3.0, -1.0, 4.0, 3.0]));
+    assert_eq!(a.values().collect::<Vec<_>>(), vec![3.0, -1.0, 4.0, 2.5]);
+}
+
+#[test]
+fn test_array_empty() {
+    let a = Array::<f32, 0>([]);
+    assert_eq!(Array::<f32, 0>::LANES, 0);
+    assert_eq!(a.reduce_sum(), 0.0);
+    assert_eq!(a.reduce_product(), 1.0);
+    assert_eq!(a.reduce_min(), f32::INFINITY);
+    assert_eq!(a.reduce_max(), f32::NEG_INFINITY);
+    assert_eq!(a.values().count(), 0);
+}
+
+#[test]
+fn test_array_conversions() {
+    let mut a = Array::from([1.0f64, 2.0]);
+    a[1] = 3.0;
+    a.as_mut()[0] = 0.5;
+    assert_eq!(a.len(), 2);
+    assert_eq!(a.as_ref(), &[0.5, 3.0]);
+    assert_eq!(<[f64; 2]>::from(a), [0.5, 3.0]);
+}
+
+#[test]
+fn test_array_nested() {
+    use tuple::T2;
+    // 2 elements of 2 lanes each
+    let data = [1.0f64, 2.0, 3.0];
+    let a = Array::<T2<f64, f64>, 2>::from_slice_padded(&data, 0.0);
+    assert_eq!(Array::<T2<f64, f64>, 2>::LANES, 4);
+    assert_eq!(a.values().collect::<Vec<_>>(), vec![1.0, 2.0, 3.0, 0.0]);
+    assert_eq!(a.reduce_sum(), 6.0);
+    assert_eq!(a.reduce_product(), 0.0);
+
+    let mut out = [0.0f64; 3];
+    (a + a).write_to_slice_partial(&mut out);
+    assert_eq!(out, [2.0, 4.0, 6.0]);
+}
@@ -1,5 +1,10 @@
 use std::ops::RangeInclusive;
+use std::convert::TryFrom;
+use std::cmp::Ordering;
 
+/// Conversion between numbers.
+/// Floats convert to integers by truncating towards zero, like `as`:
+/// a float is in range if its truncated value is. NaN and infinities are never in range.
 pub trait Cast<O>: Sized {
     /// Try to represent self as O.
     /// If possibe, return Some(Self as O) otherwhise None.
@@ -7,19 +12,46 @@ pub trait Cast<O>: Sized {
     
     /// Try to represent self within the range r of O.
     /// If possibe, return Some(self as O) otherwhise None.
+    /// An empty range (start > end) never matches.
     fn cast_clipped(self, r: RangeInclusive<O>) -> Option<O>;
     
     /// Represent self in the range r
     /// If Self is not in r, choose the nearest end of r.
-    /// (returns start <= self as O <= end)
+    /// (returns start <= self as O <= end, requires start <= end)
     fn cast_clamped(self, r: RangeInclusive<O>) -> O;
     
     /// Represent self as O
     /// If Self is not in O, choose the nearest O to self.
-    /// (returns O::MIN_VALUE <= self as O <= O::MAX_VALUE)
+    /// (returns O::MIN_VALUE <= self as O <= O::MAX_VALUE, NaN becomes 0)
     fn cast_clamping(self) -> O;
 }
 
+// cast_clipped and cast_clamped, comparing in the target type after cast and cast_clamping
+macro_rules! impl_range {
+    ($dst:ty) => (
+        #[inline(always)]
+        fn cast_clipped(self, r: RangeInclusive<$dst>) -> Option<$dst> {
+            let (start, end) = r.into_inner();
+            match self.cast() {
+                Some(v) if v >= start && v <= end => Some(v),
+                _ => None
+            }
+        }
+        #[inline(always)]
+        fn cast_clamped(self, r: RangeInclusive<$dst>) -> $dst {
+            let (start, end) = r.into_inner();
+            let v: $dst = self.cast_clamping();
+            if v < start {
+                start
+            } else if v > end {
+                end
+            } else {
+                v
+            }
+        }
+    )
+}
+
 macro_rules! impl_cast_unchecked {
     ($($src:ty as [$($dst:ty),*],)*) => (
         $( $(
@@ -29,29 +61,27 @@ macro_rules! impl_cast_unchecked {
                     Some(self as $dst)
                 }
                 #[inline(always)]
-                fn cast_clipped(self, r: RangeInclusive<$dst>) -> Option<$dst> {
-                    let (start, end) = r.into_inner();
-                    let v = self as $dst;
-                    if v >= start && v <= end {
-                        Some(v)
-                    } else {
-                        None
-                    }
-                }
-                #[inline(always)]
-                fn cast_clamped(self, r: RangeInclusive<$dst>) -> $dst {
-                    let (start, end) = r.into_inner();
-                    let v = self as $dst;
-                    if v < start {
-                        start
-                    } else if v > end {
-                        end
-                    } else {
-                        v
-                    }
+                fn cast_clamping(self) -> $dst {
+                    self as $dst
                 }
+                impl_range!($dst);
+            }
+        )* )*
+    )
+}
+
+/// Conversion that never loses information, checked at compile time.
+/// Implemented only for pairs where every value of Self is exactly representable in O.
+pub trait LosslessCast<O>: Cast<O> {
+    fn cast_lossless(self) -> O;
+}
+
+macro_rules! impl_lossless {
+    ($($src:ty as [$($dst:ty),*],)*) => (
+        $( $(
+            impl LosslessCast<$dst> for $src {
                 #[inline(always)]
-                fn cast_clamping(self) -> $dst {
+                fn cast_lossless(self) -> $dst {
                     self as $dst
                 }
             }
@@ -59,14 +89,21 @@ macro_rules! impl_cast_unchecked {
     )
 }
 
+macro_rules! impl_cast_lossless {
+    ($($src:ty as [$($dst:ty),*],)*) => (
+        impl_cast_unchecked!($($src as [$($dst),*],)*);
+        impl_lossless!($($src as [$($dst),*],)*);
+    )
+}
+
 macro_rules! impl_cast_checked {
     ($($src:ident as [$($dst:ident),*],)*) => (
         $( $(
             impl Cast<$dst> for $src {
                 #[inline(always)]
                 fn cast(self) -> Option<$dst> {
-        		    let min: $src = $dst::min_value() as $src;
-                    let max: $src = $dst::max_value() as $src;
+                    let min: $src = $dst::MIN as $src;
+                    let max: $src = $dst::MAX as $src;
                     if self >= min && self <= max {
                         Some(self as $dst)
                     } else {
@@ -74,38 +111,70 @@ macro_rules! impl_cast_checked {
                     }
                 }
                 #[inline(always)]
-                fn cast_clipped(self, r: RangeInclusive<$dst>) -> Option<$dst> {
-                    let (start, end) = r.into_inner();
-                    // if start < 0 (-> big nr), end >= 0, the check fails.
-                    // if both < 0, the check fails too
-                    // if 0 > start > end, then it passes.
-                    if start < end && self >= (start as $src) && self <= (end as $src) {
+                fn cast_clamping(self) -> $dst {
+                    let min: $src = $dst::MIN as $src;
+                    let max: $src = $dst::MAX as $src;
+                    if self < min { $dst::MIN }
+                    else if self > max { $dst::MAX }
+                    else { self as $dst }
+                }
+                impl_range!($dst);
+            }
+        )* )*
+    )
+}
+
+// Between floats. Only finite values are in range, so infinities clamp to MIN and MAX.
+macro_rules! impl_cast_float_float {
+    ($($src:ident as [$($dst:ident),*],)*) => (
+        $( $(
+            impl Cast<$dst> for $src {
+                #[inline(always)]
+                fn cast(self) -> Option<$dst> {
+                    // in f64, f64::MAX as f32 would be inf
+                    let v = self as f64;
+                    if v >= $dst::MIN as f64 && v <= $dst::MAX as f64 {
                         Some(self as $dst)
                     } else {
                         None
                     }
                 }
                 #[inline(always)]
-                fn cast_clamped(self, r: RangeInclusive<$dst>) -> $dst {
-                    let (start, end) = r.into_inner();
-                    if self >= start as $src {
-                        if self <= end as $src {
-                            self as $dst
-                        } else {
-                            end
-                        }
+                fn cast_clamping(self) -> $dst {
+                    if self.is_nan() {
+                        0.
                     } else {
-                        start
+                        (self as $dst).clamp($dst::MIN, $dst::MAX)
+                    }
+                }
+                impl_range!($dst);
+            }
+        )* )*
+    )
+}
+
+// Float to integer. A float is in range if its truncated value is,
+// so 255.9 becomes 255u8 like with `as`.
+// MAX as float may round up to MAX + 1, which is why the upper bound is exclusive.
+macro_rules! impl_cast_float {
+    ($($src:ident as [$($dst:ident),*],)*) => (
+        $( $(
+            impl Cast<$dst> for $src {
+                #[inline(always)]
+                fn cast(self) -> Option<$dst> {
+                    let t = self.trunc();
+                    if t >= $dst::MIN as $src && t < $dst::MAX as $src + 1.0 {
+                        Some(t as $dst)
+                    } else {
+                        None
                     }
                 }
                 #[inline(always)]
                 fn cast_clamping(self) -> $dst {
-        		    let min: $src = $dst::min_value() as $src;
-                    let max: $src = $dst::max_value() as $src;
-                    if self < min { $dst::min_value() }
-                    else if self > max { $dst::max_value() }
-                    else { self as $dst }
+                    // saturates, and NaN becomes 0
+                    self as $dst
                 }
+                impl_range!($dst);
             }
         )* )*
     )
@@ -117,6 +186,10 @@ fn test_clip_checked() {
     assert_eq!(3f32.cast_clipped(0u16..=5), Some(3));
     assert_eq!(100f32.cast_clipped(0usize..=1000), Some(100));
     assert_eq!(Cast::<u8>::cast_clamping(300i16), 255u8);
+    assert_eq!(5u16.cast_clipped(5u8..=5), Some(5));
+    assert_eq!(5.5f32.cast_clipped(0u8..=5), Some(5));
+    assert_eq!(5i32.cast_clamped(0u32..=u32::MAX), 5);
+    assert_eq!(Cast::<u32>::cast(4294967296f32), None);
 }
 
 macro_rules! impl_cast_signed {
@@ -132,29 +205,6 @@ macro_rules! impl_cast_signed {
                     }
                 }
                 #[inline(always)]
-                fn cast_clipped(self, r: RangeInclusive<$unsigned>) -> Option<$unsigned> {
-                    let (start, end) = r.into_inner();
-                    let start = start as $signed;
-                    let u = self as $unsigned;
-                    if start >= 0 && self >= start && u <= end {
-                        Some(u)
-                    } else {
-                        None
-                    }
-                }
-                #[inline(always)]
-                fn cast_clamped(self, r: RangeInclusive<$unsigned>) -> $unsigned {        
-                    let (start, end) = r.into_inner();                     
-                    let s = start as $signed;
-                    if s < 0 || self < s {
-                        start
-                    } else if self > end as $signed {
-                        end
-                    } else {
-                        self as $unsigned
-                    }
-                }
-                #[inline(always)]
                 fn cast_clamping(self) -> $unsigned {
                     if self >= 0 {
                         self as $unsigned
@@ -162,6 +212,7 @@ macro_rules! impl_cast_signed {
                         0
                     }
                 }
+                impl_range!($unsigned);
             }
             impl Cast<$signed> for $unsigned {
                 #[inline(always)]
@@ -174,164 +225,155 @@ macro_rules! impl_cast_signed {
                     }
                 }
                 #[inline(always)]
-                fn cast_clipped(self, r: RangeInclusive<$signed>) -> Option<$signed> {
-                    let (start, end) = r.into_inner();
-                    let s = self as $signed;
-                    if s >= 0 && s >= start && s <= end {
-                        Some(s)
-                    } else {
-                        None
-                    }
-                }
-                #[inline(always)]
-                fn cast_clamped(self, r: RangeInclusive<$signed>) -> $signed {
-                    let (start, end) = r.into_inner();
-                    let s = self as $signed;
-                    if s < 0 || s > end {
-                        end
-                    } else if s < start {
-                        start
-                    } else {
-                        s
-                    }
-                }
-                #[inline(always)]
                 fn cast_clamping(self) -> $signed {
                     let s = self as $signed;
                     if s >= 0 {
                         s
                     } else {
-                        $signed::max_value()
+                        $signed::MAX
                     }
                 }
+                impl_range!($signed);
             }
         )*
     )
 }
-macro_rules! impl_cast_id {
-    ($( $a:ty, $b:ty; )*) => (
-        $(
-            impl Cast<$b> for $a {
-                #[inline(always)]
-                fn cast(self) -> Option<$b> {
-                    Some(self as $b)
-                }
+
+// integer pairs of mixed sign and width, checked with TryFrom
+macro_rules! impl_cast_int {
+    ($($src:ident as [$($dst:ident),*],)*) => (
+        $( $(
+            impl Cast<$dst> for $src {
                 #[inline(always)]
-                fn cast_clipped(self, r: RangeInclusive<$b>) -> Option<$b> {
-                    let (start, end) = r.into_inner();
-                    let b = self as $b;
-                    if b >= start && b <= end {
-                        Some(b)
-                    } else {
-                        None
-                    }
+                fn cast(self) -> Option<$dst> {
+                    $dst::try_from(self).ok()
                 }
                 #[inline(always)]
-                fn cast_clamped(self, r: RangeInclusive<$b>) -> $b {
-                    let (start, end) = r.into_inner();
-                    let b = self as $b;
-                    if b < start {
-                        start
-                    } else if b > end {
-                        end
-                    } else {
-                        b
+                fn cast_clamping(self) -> $dst {
+                    match $dst::try_from(self) {
+                        Ok(v) => v,
+                        Err(_) if self > 0 => $dst::MAX,
+                        Err(_) => $dst::MIN
                     }
                 }
-                #[inline(always)]
-                fn cast_clamping(self) -> $b {
-                    self as $b
-                }
-                
+                impl_range!($dst);
             }
-            impl Cast<$a> for $b {
+        )* )*
+    )
+}
+
+// bool and char convert like the integer they are stored as
+macro_rules! impl_cast_via {
+    ($($src:ident as $via:ident => [$($dst:ident),*],)*) => (
+        $( $(
+            impl Cast<$dst> for $src {
                 #[inline(always)]
-                fn cast(self) -> Option<$a> {
-                    Some(self as $a)
+                fn cast(self) -> Option<$dst> {
+                    (self as $via).cast()
                 }
                 #[inline(always)]
-                fn cast_clipped(self, r: RangeInclusive<$a>) -> Option<$a> {
-                    let (start, end) = r.into_inner();
-                    let a = self as $a;
-                    if a >= start && a <= end {
-                        Some(a)
-                    } else {
-                        None
-                    }
+                fn cast_clipped(self, r: RangeInclusive<$dst>) -> Option<$dst> {
+                    (self as $via).cast_clipped(r)
                 }
                 #[inline(always)]
-                fn cast_clamped(self, r: RangeInclusive<$a>) -> $a {
-                    let (start, end) = r.into_inner();
-                    let a = self as $a;
-                    if a < start {
-                        start
-                    } else if a > end {
-                        end
-                    } else {
-                        a
-                    }
+                fn cast_clamped(self, r: RangeInclusive<$dst>) -> $dst {
+                    (self as $via).cast_clamped(r)
                 }
                 #[inline(always)]
-                fn cast_clamping(self) -> $a {
-                    self as $a
+                fn cast_clamping(self) -> $dst {
+                    (self as $via).cast_clamping()
                 }
             }
-        )*
+        )* )*
     )
 }
-impl_cast_unchecked!(
-     u8 as [    u8, i16, u16, i32, u32, i64, u64, i128, u128, f32, f64, usize, isize],
-    u16 as [             u16, i32, u32, i64, u64, i128, u128, f32, f64, usize, isize],
-    u32 as [                       u32,      u64, i128, u128, f32, f64              ],
-    u64 as [                                 u64,       u128, f32, f64              ],
-     i8 as [i8,     i16,      i32,      i64,      i128,       f32, f64,        isize],
-    i16 as [        i16,      i32,      i64,      i128, u128, f32, f64,        isize],
-    i32 as [                  i32,      i64,      i128, u128, f32, f64              ],
-    i64 as [                            i64,      i128, u128, f32, f64              ],
-    f32 as [                                                  f32, f64              ],
-    f64 as [                                                       f64              ],
+impl_cast_lossless!(
+     u8 as [    u8, i16, u16, i32, u32, i64, u64, i128, u128, f32, f64],
+    u16 as [             u16, i32, u32, i64, u64, i128, u128, f32, f64],
+    u32 as [                       u32, i64, u64, i128, u128,      f64],
+    u64 as [                                 u64, i128, u128          ],
+   u128 as [                                            u128          ],
+     i8 as [i8,     i16,      i32,      i64,      i128,       f32, f64],
+    i16 as [        i16,      i32,      i64,      i128,       f32, f64],
+    i32 as [                  i32,      i64,      i128,            f64],
+    i64 as [                            i64,      i128                ],
+   i128 as [                                      i128                ],
+  usize as [                                                            usize],
+  isize as [                                                            isize],
 );
-#[cfg(target_pointer_width = "32")]
-impl_cast_unchecked!(
-  usize as [                         u64, i128, u128, f32, f64],
-  isize as [                    i64,      i128, u128, f32, f64],
+// exact as well, `Cast` between floats is below
+impl_lossless!(
+    f32 as [f32, f64],
+    f64 as [     f64],
 );
-#[cfg(target_pointer_width = "64")]
+// may round
 impl_cast_unchecked!(
-    u32 as [                                                    usize],
-  usize as [                                    u128, f32, f64],
-  isize as [                              i128, u128, f32, f64],
+    u32 as [f32     ],
+    u64 as [f32, f64],
+   u128 as [     f64],
+    i32 as [f32     ],
+    i64 as [f32, f64],
+   i128 as [f32, f64],
+  usize as [f32, f64],
+  isize as [f32, f64],
 );
 impl_cast_checked!(
-    u16 as [u8, i8                                   ],
+    u16 as [u8                                       ],
     i16 as [u8, i8                                   ],
-    u32 as [u8, i8, u16, i16                         ],
+    u32 as [u8,     u16                              ],
     i32 as [u8, i8, u16, i16                         ],
-    u64 as [u8, i8, u16, i16, u32, i32               ],
+    u64 as [u8,     u16,      u32                    ],
     i64 as [u8, i8, u16, i16, u32, i32               ],
-    f32 as [u8, i8, u16, i16, u32, i32, u64, i64     , usize, isize],
-    f64 as [u8, i8, u16, i16, u32, i32, u64, i64, f32, usize, isize],
+    // u128::MAX is beyond f32::MAX and would become inf
+   u128 as [                                                  f32             ],
 );
-#[cfg(target_pointer_width = "32")]
-impl_cast_checked!(
-    u64 as [                                           usize],
-  usize as [u8, i8, u16, i16                         ],
-  isize as [u8, i8, u16, i16                         ],
+impl_cast_float_float!(
+    f32 as [f32, f64],
+    f64 as [f32, f64],
 );
-#[cfg(target_pointer_width = "64")]
-impl_cast_checked!(
-  usize as [u8, i8, u16, i16, u32, i32               ],
-  isize as [u8, i8, u16, i16, u32, i32               ],
+impl_cast_float!(
+    f32 as [u8, i8, u16, i16, u32, i32, u64, i64, u128, i128,      usize, isize],
+    f64 as [u8, i8, u16, i16, u32, i32, u64, i64, u128, i128,      usize, isize],
 );
+impl_cast_signed!(u8, i8; u16, i16; u32, i32; u64, i64; u128, i128;);
 
-impl_cast_signed!(u8, i8; u16, i16; u32, i32; u64, i64; usize, isize;);
-
-#[cfg(target_pointer_width = "32")]
-impl_cast_id!(usize, u32; isize, i32;);
+// usize and isize go through here as well, so the matrix does not depend on the pointer width
+impl_cast_int!(
+    u16 as [    i8                                                       ],
+    u32 as [    i8,      i16                                             ],
+    u64 as [    i8,      i16,      i32                                   ],
+   u128 as [u8, i8, u16, i16, u32, i32, u64, i64                         ],
+     i8 as [        u16,      u32,      u64,      u128                   ],
+    i16 as [                  u32,      u64,      u128                   ],
+    i32 as [                            u64,      u128                   ],
+    i64 as [                                      u128                   ],
+   i128 as [u8, i8, u16, i16, u32, i32, u64, i64                         ],
+     u8 as [                                                usize, isize],
+    u16 as [                                                usize, isize],
+    u32 as [                                                usize, isize],
+    u64 as [                                                usize, isize],
+   u128 as [                                                usize, isize],
+     i8 as [                                                usize, isize],
+    i16 as [                                                usize, isize],
+    i32 as [                                                usize, isize],
+    i64 as [                                                usize, isize],
+   i128 as [                                                usize, isize],
+  usize as [u8, i8, u16, i16, u32, i32, u64, i64, u128, i128,        isize],
+  isize as [u8, i8, u16, i16, u32, i32, u64, i64, u128, i128, usize       ],
+);
 
-#[cfg(target_pointer_width = "64")]
-impl_cast_id!(usize, u64; isize, i64;);
+// usize has at least 16 bits
+impl_lossless!(
+     u8 as [usize, isize],
+    u16 as [usize       ],
+     i8 as [       isize],
+    i16 as [       isize],
+);
 
+impl_cast_via!(
+   bool as u8 => [u8, i8, u16, i16, u32, i32, u64, i64, u128, i128, usize, isize, f32, f64],
+   char as u32 => [u8, i8, u16, i16, u32, i32, u64, i64, u128, i128, usize, isize, f32, f64],
+);
 
 use tuple::*;
 macro_rules! impl_cast {
@@ -370,15 +412,689 @@ macro_rules! impl_cast {
 
 impl_tuple!(impl_cast);
 
-trait MinMax {
-    fn min_value() -> Self;
-    fn max_value() -> Self;
+impl<T, O, const N: usize> Cast<[O; N]> for [T; N] where T: Cast<O> {
+    #[inline]
+    fn cast(self) -> Option<[O; N]> {
+        let o = self.map(T::cast);
+        if o.iter().all(Option::is_some) {
+            Some(o.map(Option::unwrap))
+        } else {
+            None
+        }
+    }
+    #[inline]
+    fn cast_clipped(self, r: RangeInclusive<[O; N]>) -> Option<[O; N]> {
+        let (start, end) = r.into_inner();
+        let (mut start, mut end) = (IntoIterator::into_iter(start), IntoIterator::into_iter(end));
+        let o = self.map(|x| x.cast_clipped(start.next().unwrap() ..= end.next().unwrap()));
+        if o.iter().all(Option::is_some) {
+            Some(o.map(Option::unwrap))
+        } else {
+            None
+        }
+    }
+    #[inline]
+    fn cast_clamped(self, r: RangeInclusive<[O; N]>) -> [O; N] {
+        let (start, end) = r.into_inner();
+        let (mut start, mut end) = (IntoIterator::into_iter(start), IntoIterator::into_iter(end));
+        self.map(|x| x.cast_clamped(start.next().unwrap() ..= end.next().unwrap()))
+    }
+    #[inline]
+    fn cast_clamping(self) -> [O; N] {
+        self.map(T::cast_clamping)
+    }
 }
-impl MinMax for f32 {
-    fn min_value() -> f32 { ::std::f32::MIN }
-    fn max_value() -> f32 { ::std::f32::MAX }
+
+impl<T, O, const N: usize> LosslessCast<[O; N]> for [T; N] where T: LosslessCast<O> {
+    #[inline]
+    fn cast_lossless(self) -> [O; N] {
+        self.map(T::cast_lossless)
+    }
 }
-impl MinMax for f64 {
-    fn min_value() -> f64 { ::std::f64::MIN }
-    fn max_value() -> f64 { ::std::f64::MAX }
+
+macro_rules! impl_lossless_tuple {
+    ($($Tuple:ident $Arr:ident { $($T:ident . $t:ident . $idx:tt),* } )*) => ($(
+        #[allow(non_camel_case_types)]
+        impl<$($T, $t),*> LosslessCast<$Tuple<$($t),*>> for $Tuple<$($T),*>
+        where $( $T: LosslessCast<$t> ),*
+        {
+            #[inline(always)]
+            fn cast_lossless(self) -> $Tuple<$($t),*> {
+                $Tuple( $(self.$idx.cast_lossless()),* )
+            }
+        }
+    )*)
+}
+impl_tuple!(impl_lossless_tuple);
+
+#[test]
+fn test_cast_lossless() {
+    fn widen<T: LosslessCast<f64>>(x: T) -> f64 { x.cast_lossless() }
+    assert_eq!(widen(-3i32), -3.);
+    assert_eq!(widen(200u8), 200.);
+    assert_eq!(widen(0.1f32), 0.1f32 as f64);
+    assert_eq!(LosslessCast::<f32>::cast_lossless(u16::MAX), 65535.);
+    assert_eq!(LosslessCast::<usize>::cast_lossless(7u8), 7);
+    assert_eq!(T2(1u8, 2i16).cast_lossless(), T2(1u32, 2i64));
+    assert_eq!(LosslessCast::<[f32; 2]>::cast_lossless([1u8, 2]), [1., 2.]);
+}
+
+/// Cast whole slices. Some pairs use SIMD when the `simd` feature is enabled and the cpu has SSE4.1.
+///
+/// The methods panic if input and out differ in length.
+/// Use the functions `cast_slice`, `cast_slice_clamping` and `cast_slice_clamped`.
+pub trait CastSlice<O>: Cast<O> + Copy {
+    /// Returns None if any value is out of range. out is partially written then.
+    #[inline]
+    fn cast_slice(input: &[Self], out: &mut [O]) -> Option<()> {
+        assert_eq!(input.len(), out.len());
+        for (&i, o) in input.iter().zip(out.iter_mut()) {
+            *o = i.cast()?;
+        }
+        Some(())
+    }
+
+    #[inline]
+    fn cast_slice_clamping(input: &[Self], out: &mut [O]) {
+        assert_eq!(input.len(), out.len());
+        for (&i, o) in input.iter().zip(out.iter_mut()) {
+            *o = i.cast_clamping();
+        }
+    }
+
+    #[inline]
+    fn cast_slice_clamped(input: &[Self], out: &mut [O], r: RangeInclusive<O>) where O: Copy {
+        assert_eq!(input.len(), out.len());
+        let (start, end) = r.into_inner();
+        for (&i, o) in input.iter().zip(out.iter_mut()) {
+            *o = i.cast_clamped(start ..= end);
+        }
+    }
+}
+
+/// Cast every value of input into out, see `Cast::cast`.
+/// Returns None if any value is out of range.
+/// panics if input and out differ in length.
+#[inline]
+pub fn cast_slice<T: CastSlice<O>, O>(input: &[T], out: &mut [O]) -> Option<()> {
+    T::cast_slice(input, out)
+}
+
+/// Cast every value of input into out, see `Cast::cast_clamping`.
+/// panics if input and out differ in length.
+#[inline]
+pub fn cast_slice_clamping<T: CastSlice<O>, O>(input: &[T], out: &mut [O]) {
+    T::cast_slice_clamping(input, out)
+}
+
+/// Cast every value of input into out, see `Cast::cast_clamped`.
+/// panics if input and out differ in length.
+#[inline]
+pub fn cast_slice_clamped<T: CastSlice<O>, O: Copy>(input: &[T], out: &mut [O], r: RangeInclusive<O>) {
+    T::cast_slice_clamped(input, out, r)
+}
+
+macro_rules! impl_cast_slice {
+    ([$($src:ident),*] => $dst:tt) => ( $( impl_cast_slice!(@ $src => $dst); )* );
+    (@ $src:ident => [$($dst:ident),*]) => ( $(
+        impl CastSlice<$dst> for $src {}
+    )* );
+}
+impl_cast_slice!([u64, u128, usize, i64, i128, isize, f64, bool, char]
+    => [u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64]);
+impl_cast_slice!([u8, u16, u32, i8, i16, i32]
+    => [u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f64]);
+impl_cast_slice!([f32] => [u64, u128, usize, i64, i128, isize, f32, f64]);
+
+// these have vector versions in `simd`
+#[cfg(not(feature="simd"))]
+impl_cast_slice!([u8, u16, u32, i8, i16, i32] => [f32]);
+#[cfg(not(feature="simd"))]
+impl_cast_slice!([f32] => [u8, u16, u32, i8, i16, i32]);
+
+#[test]
+fn test_cast_array() {
+    assert_eq!([1.5f32, 300.].cast(), None::<[u8; 2]>);
+    assert_eq!(Cast::<[u8; 2]>::cast_clamping([1.5f32, 300.]), [1, 255]);
+    assert_eq!([1i32, 5].cast_clipped([0u8, 0] ..= [1, 4]), None);
+    assert_eq!([1i32, 5].cast_clamped([0u8, 0] ..= [1, 4]), [1, 4]);
+}
+
+#[test]
+fn test_cast_slice() {
+    let input: Vec<i16> = (-10 .. 10).map(|i| i * 3000).collect();
+    let mut out = vec![0f32; input.len()];
+    assert_eq!(cast_slice(&input, &mut out), Some(()));
+    assert!(input.iter().zip(&out).all(|(&i, &o)| i as f32 == o));
+
+    let mut back = vec![0u8; out.len()];
+    assert_eq!(cast_slice(&out, &mut back), None);
+    cast_slice_clamping(&out, &mut back);
+    assert!(out.iter().zip(&back).all(|(&o, &b)| Cast::<u8>::cast_clamping(o) == b));
+    cast_slice_clamped(&out, &mut back, 10 ..= 20);
+    assert!(out.iter().zip(&back).all(|(&o, &b)| o.cast_clamped(10u8 ..= 20) == b));
+}
+
+/// How a float is rounded to an integral value.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Rounding {
+    /// to the nearest integer, ties to the even one
+    NearestEven,
+    /// to the nearest integer, ties away from zero
+    NearestAway,
+    /// towards -inf
+    Floor,
+    /// towards +inf
+    Ceil,
+    /// towards zero (what `as` does)
+    Trunc,
+}
+
+/// Round to an integral value, lane-wise for tuples and vectors.
+pub trait Round: Sized {
+    fn round_with(self, mode: Rounding) -> Self;
+}
+
+macro_rules! impl_round {
+    ($($t:ident),*) => ( $(
+        impl Round for $t {
+            #[inline]
+            fn round_with(self, mode: Rounding) -> $t {
+                match mode {
+                    Rounding::NearestEven => $t::round_ties_even(self),
+                    Rounding::NearestAway => self.round(),
+                    Rounding::Floor => self.floor(),
+                    Rounding::Ceil => self.ceil(),
+                    Rounding::Trunc => self.trunc(),
+                }
+            }
+        }
+    )* )
+}
+impl_round!(f32, f64);
+
+macro_rules! impl_round_tuple {
+    ($($Tuple:ident $Arr:ident { $($T:ident . $t:ident . $idx:tt),* } )*) => ($(
+        impl<$($T: Round),*> Round for $Tuple<$($T),*> {
+            #[inline]
+            fn round_with(self, mode: Rounding) -> Self {
+                $Tuple( $(self.$idx.round_with(mode)),* )
+            }
+        }
+    )*)
+}
+impl_tuple!(impl_round_tuple);
+
+/// Cast after rounding with the given mode.
+/// The methods match those of `Cast`.
+pub trait CastRounded<O>: Sized {
+    fn cast_rounded(self, mode: Rounding) -> Option<O>;
+    fn cast_rounded_clipped(self, mode: Rounding, r: RangeInclusive<O>) -> Option<O>;
+    fn cast_rounded_clamped(self, mode: Rounding, r: RangeInclusive<O>) -> O;
+    fn cast_rounded_clamping(self, mode: Rounding) -> O;
+}
+
+impl<T: Round + Cast<O>, O> CastRounded<O> for T {
+    #[inline(always)]
+    fn cast_rounded(self, mode: Rounding) -> Option<O> {
+        self.round_with(mode).cast()
+    }
+    #[inline(always)]
+    fn cast_rounded_clipped(self, mode: Rounding, r: RangeInclusive<O>) -> Option<O> {
+        self.round_with(mode).cast_clipped(r)
+    }
+    #[inline(always)]
+    fn cast_rounded_clamped(self, mode: Rounding, r: RangeInclusive<O>) -> O {
+        self.round_with(mode).cast_clamped(r)
+    }
+    #[inline(always)]
+    fn cast_rounded_clamping(self, mode: Rounding) -> O {
+        self.round_with(mode).cast_clamping()
+    }
+}
+
+#[test]
+fn test_cast_rounded() {
+    use self::Rounding::*;
+    assert_eq!(2.5f32.cast_rounded(NearestEven), Some(2u8));
+    assert_eq!(3.5f32.cast_rounded(NearestEven), Some(4u8));
+    assert_eq!((-2.5f64).cast_rounded(NearestEven), Some(-2i32));
+    assert_eq!(2.5f32.cast_rounded(NearestAway), Some(3u8));
+    assert_eq!((-2.5f64).cast_rounded(NearestAway), Some(-3i32));
+    assert_eq!(2.7f32.cast_rounded(Floor), Some(2u8));
+    assert_eq!(2.2f32.cast_rounded(Ceil), Some(3u8));
+    assert_eq!(2.7f32.cast_rounded(Trunc), Some(2u8));
+    assert_eq!(0.49999997f32.cast_rounded(NearestAway), Some(0u8));
+    assert_eq!(0.49999997f32.cast_rounded(NearestEven), Some(0u8));
+    assert_eq!(Cast::<u8>::cast_clamping(254.6f32.round_with(NearestEven)), 255);
+    assert_eq!(300.2f32.cast_rounded_clamped(NearestEven, 0u16 ..= 255), 255);
+    assert_eq!(T2(1.5f32, 0.5f64).cast_rounded(NearestEven), Some(T2(2u8, 0i8)));
+}
+
+/// Conversion that succeeds only if no information is lost.
+pub trait CastExact<O>: Sized {
+    /// Return Some(self as O) if it converts back to self exactly, otherwhise None.
+    /// Fractional floats, integers beyond the mantissa of a float,
+    /// precision lost from f64 to f32 and NaN are all rejected.
+    fn cast_exact(self) -> Option<O>;
+}
+
+// The float cases compare the value after a round trip. The only way to fool
+// this is saturation at the integer maximum, which is excluded unless the maximum
+// is exactly representable in the float.
+macro_rules! impl_cast_exact {
+    ($kind:ident: [$($src:ident),*] => $dst:tt) => (
+        $( impl_cast_exact!(@$kind $src => $dst); )*
+    );
+    (@int $src:ident => [$($dst:ident),*]) => ( $(
+        impl CastExact<$dst> for $src {
+            #[inline(always)]
+            fn cast_exact(self) -> Option<$dst> {
+                $dst::try_from(self).ok()
+            }
+        }
+    )* );
+    (@int_float $src:ident => [$($dst:ident),*]) => ( $(
+        impl CastExact<$dst> for $src {
+            #[inline(always)]
+            fn cast_exact(self) -> Option<$dst> {
+                let f = self as $dst;
+                let back = f as $src;
+                if back == self && (back != $src::MAX || $src::MAX.count_ones() <= $dst::MANTISSA_DIGITS) {
+                    Some(f)
+                } else {
+                    None
+                }
+            }
+        }
+    )* );
+    (@float_int $src:ident => [$($dst:ident),*]) => ( $(
+        impl CastExact<$dst> for $src {
+            #[inline(always)]
+            fn cast_exact(self) -> Option<$dst> {
+                let i = self as $dst;
+                if i as $src == self && (i != $dst::MAX || $dst::MAX.count_ones() <= $src::MANTISSA_DIGITS) {
+                    Some(i)
+                } else {
+                    None
+                }
+            }
+        }
+    )* );
+    (@float $src:ident => [$($dst:ident),*]) => ( $(
+        impl CastExact<$dst> for $src {
+            #[inline(always)]
+            fn cast_exact(self) -> Option<$dst> {
+                let f = self as $dst;
+                if f as $src == self {
+                    Some(f)
+                } else {
+                    None
+                }
+            }
+        }
+    )* );
+}
+
+impl_cast_exact!(int: [u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize]
+    => [u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize]);
+impl_cast_exact!(int_float: [u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize] => [f32, f64]);
+impl_cast_exact!(float_int: [f32, f64] => [u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize]);
+impl_cast_exact!(float: [f32, f64] => [f32, f64]);
+
+/// Conversion with wrap-around instead of saturation.
+///
+/// Integers wrap modulo 2^bits of the target, like `as`.
+/// Floats are truncated first and wrapped the same way, NaN and inf become 0.
+/// f32 to f32 or f64 and f64 to f64 cannot overflow and convert like `as`.
+/// f64 to f32 wraps finite values into [-f32::MAX, f32::MAX], modulo 2 f32::MAX,
+/// NaN and inf become 0.
+pub trait CastWrapping<O>: Sized {
+    fn cast_wrapping(self) -> O;
+}
+
+macro_rules! impl_cast_wrapping {
+    ($kind:ident: [$($src:ident),*] => $dst:tt) => (
+        $( impl_cast_wrapping!(@$kind $src => $dst); )*
+    );
+    (@as $src:ident => [$($dst:ident),*]) => ( $(
+        impl CastWrapping<$dst> for $src {
+            #[inline(always)]
+            fn cast_wrapping(self) -> $dst {
+                self as $dst
+            }
+        }
+    )* );
+    (@float_int $src:ident => [$($dst:ident),*]) => ( $(
+        impl CastWrapping<$dst> for $src {
+            #[inline]
+            fn cast_wrapping(self) -> $dst {
+                if !self.is_finite() {
+                    return 0;
+                }
+                // 2^bits divides 2^128, so reducing modulo 2^128 first is enough.
+                // rem_euclid is exact for the magnitude, but would round when it
+                // adds 2^128 to a negative value, so the sign is applied as an integer.
+                let t = (self as f64).trunc();
+                let m = t.abs().rem_euclid(TWO_128) as u128;
+                if t < 0. { m.wrapping_neg() as $dst } else { m as $dst }
+            }
+        }
+    )* );
+}
+
+const TWO_128: f64 = 340282366920938463463374607431768211456.;
+
+impl_cast_wrapping!(as: [u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize]
+    => [u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64]);
+impl_cast_wrapping!(float_int: [f32, f64] => [u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize]);
+
+macro_rules! impl_cast_wrapping_float {
+    ($($src:ident as $dst:ident),*) => ( $(
+        impl CastWrapping<$dst> for $src {
+            #[inline]
+            fn cast_wrapping(self) -> $dst {
+                self as $dst
+            }
+        }
+    )* )
+}
+impl_cast_wrapping_float!(f32 as f32, f32 as f64, f64 as f64);
+
+impl CastWrapping<f32> for f64 {
+    #[inline]
+    fn cast_wrapping(self) -> f32 {
+        if !self.is_finite() {
+            return 0.;
+        }
+        // wrapping is symmetric, so the magnitude is wrapped with rem_euclid
+        // and the sign restored afterwards. All steps are exact.
+        let max = f32::MAX as f64;
+        let r = self.abs().rem_euclid(2. * max);
+        let r = if r > max { r - 2. * max } else { r };
+        (if self < 0. { -r } else { r }) as f32
+    }
+}
+
+macro_rules! impl_cast_exact_tuple {
+    ($($Tuple:ident $Arr:ident { $($T:ident . $t:ident . $idx:tt),* } )*) => ($(
+        #[allow(non_camel_case_types)]
+        impl<$($T, $t),*> CastExact<$Tuple<$($t),*>> for $Tuple<$($T),*>
+        where $( $T: CastExact<$t> ),*
+        {
+            #[inline(always)]
+            fn cast_exact(self) -> Option<$Tuple<$($t),*>> {
+                match ( $(self.$idx.cast_exact(), )* ) {
+                    ( $( Some($t), )* ) => Some($Tuple($($t),*)),
+                    _ => None
+                }
+            }
+        }
+    )*)
+}
+impl_tuple!(impl_cast_exact_tuple);
+
+macro_rules! impl_cast_wrapping_tuple {
+    ($($Tuple:ident $Arr:ident { $($T:ident . $t:ident . $idx:tt),* } )*) => ($(
+        #[allow(non_camel_case_types)]
+        impl<$($T, $t),*> CastWrapping<$Tuple<$($t),*>> for $Tuple<$($T),*>
+        where $( $T: CastWrapping<$t> ),*
+        {
+            #[inline(always)]
+            fn cast_wrapping(self) -> $Tuple<$($t),*> {
+                $Tuple( $(self.$idx.cast_wrapping()),* )
+            }
+        }
+    )*)
+}
+impl_tuple!(impl_cast_wrapping_tuple);
+
+#[test]
+fn test_cast_wrapping() {
+    assert_eq!(CastWrapping::<u8>::cast_wrapping(300i32), 44);
+    assert_eq!(CastWrapping::<i8>::cast_wrapping(200u8), -56);
+    assert_eq!(CastWrapping::<u8>::cast_wrapping(300.7f32), 44);
+    assert_eq!(CastWrapping::<u8>::cast_wrapping(-1.5f64), 255);
+    assert_eq!(CastWrapping::<u8>::cast_wrapping(-257f64), 255);
+    assert_eq!(CastWrapping::<i32>::cast_wrapping(4294967297f64), 1);
+    assert_eq!(CastWrapping::<u64>::cast_wrapping(-3f64), u64::MAX - 2);
+    assert_eq!(CastWrapping::<u128>::cast_wrapping(-3f32), u128::MAX - 2);
+    assert_eq!(CastWrapping::<i64>::cast_wrapping(-1e20f64), -1e20f64 as i128 as i64);
+    assert_eq!(CastWrapping::<i8>::cast_wrapping(f32::NAN), 0);
+    assert_eq!(CastWrapping::<u16>::cast_wrapping(f64::INFINITY), 0);
+    assert_eq!(CastWrapping::<f32>::cast_wrapping(1.5f64), 1.5);
+    assert_eq!(CastWrapping::<f32>::cast_wrapping(1.5 * f32::MAX as f64), -0.5 * f32::MAX);
+    assert_eq!(CastWrapping::<f32>::cast_wrapping(-1.5 * f32::MAX as f64), 0.5 * f32::MAX);
+    assert_eq!(CastWrapping::<f32>::cast_wrapping(-2. * f32::MAX as f64 - 2f64.powi(80)), -2f32.powi(80));
+    assert!(CastWrapping::<f32>::cast_wrapping(-1e300f64).is_finite());
+    assert_eq!(CastWrapping::<f64>::cast_wrapping(f32::NEG_INFINITY), f64::NEG_INFINITY);
+    assert_eq!(T2(300u16, -1i8).cast_wrapping(), T2(44u8, 255u8));
+}
+
+#[test]
+fn test_cast_exact() {
+    assert_eq!(CastExact::<i32>::cast_exact(2.5f64), None);
+    assert_eq!(CastExact::<i32>::cast_exact(-2.0f64), Some(-2));
+    assert_eq!(CastExact::<u8>::cast_exact(-1.0f32), None);
+    assert_eq!(CastExact::<i32>::cast_exact(2147483648f32), None);
+    assert_eq!(CastExact::<i32>::cast_exact(-2147483648f32), Some(i32::MIN));
+    assert_eq!(CastExact::<u8>::cast_exact(255f32), Some(255));
+    assert_eq!(CastExact::<u8>::cast_exact(f32::NAN), None);
+
+    assert_eq!(CastExact::<f32>::cast_exact(u64::MAX), None);
+    assert_eq!(CastExact::<f64>::cast_exact((1u64 << 53) + 1), None);
+    assert_eq!(CastExact::<f64>::cast_exact(1u64 << 53), Some(9007199254740992.));
+    assert_eq!(CastExact::<f32>::cast_exact(u16::MAX), Some(65535.));
+    assert_eq!(CastExact::<f32>::cast_exact(i32::MIN), Some(-2147483648.));
+
+    assert_eq!(CastExact::<f32>::cast_exact(0.1f64), None);
+    assert_eq!(CastExact::<f32>::cast_exact(0.5f64), Some(0.5));
+    assert_eq!(CastExact::<f32>::cast_exact(f64::NAN), None);
+    assert_eq!(CastExact::<f64>::cast_exact(f32::INFINITY), Some(f64::INFINITY));
+
+    assert_eq!(CastExact::<u8>::cast_exact(256u16), None);
+    assert_eq!(CastExact::<u32>::cast_exact(-1i64), None);
+    assert_eq!(T2(3.0f32, 7u64).cast_exact(), Some(T2(3u8, 7f64)));
+    assert_eq!(CastExact::<T2<u8, f64>>::cast_exact(T2(3.5f32, 7u64)), None);
+}
+
+/// Why a conversion failed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CastError {
+    /// the value is above the target range
+    TooLarge,
+    /// the value is below the target range
+    TooSmall,
+    /// the value is NaN
+    NaN,
+    /// the value is in range but would lose a fractional part or precision
+    InexactFraction,
+    /// the requested range has start > end
+    EmptyRange,
+    /// the given component of a tuple failed
+    Component(usize, Box<CastError>),
+}
+
+impl ::std::fmt::Display for CastError {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        match *self {
+            CastError::TooLarge => write!(f, "value too large for the target"),
+            CastError::TooSmall => write!(f, "value too small for the target"),
+            CastError::NaN => write!(f, "value is NaN"),
+            CastError::InexactFraction => write!(f, "value can not be represented exactly"),
+            CastError::EmptyRange => write!(f, "empty target range"),
+            CastError::Component(i, ref e) => write!(f, "component {}: {}", i, e),
+        }
+    }
+}
+
+impl ::std::error::Error for CastError {}
+
+/// Like `Cast` and `CastExact`, but reports the reason of a failure.
+pub trait TryCast<O>: Sized {
+    fn try_cast(self) -> Result<O, CastError>;
+    fn try_cast_clipped(self, r: RangeInclusive<O>) -> Result<O, CastError>;
+    fn try_cast_exact(self) -> Result<O, CastError>;
+}
+
+// the primitive numbers, whose failures can be told apart by comparing with zero
+trait Primitive: Copy + PartialOrd + Default {}
+macro_rules! impl_primitive {
+    ($($t:ty),*) => ( $( impl Primitive for $t {} )* )
+}
+impl_primitive!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64);
+
+#[inline]
+fn is_nan<T: Primitive>(value: T) -> bool {
+    value.partial_cmp(&value).is_none()
+}
+
+// only used once a cast of `value` failed
+#[inline]
+fn failure<T: Primitive>(value: T) -> CastError {
+    if is_nan(value) {
+        CastError::NaN
+    } else if value > T::default() {
+        CastError::TooLarge
+    } else {
+        CastError::TooSmall
+    }
+}
+
+impl<T, O> TryCast<O> for T where T: Primitive + Cast<O> + CastExact<O>, O: Primitive {
+    #[inline]
+    fn try_cast(self) -> Result<O, CastError> {
+        self.cast().ok_or_else(|| failure(self))
+    }
+    #[inline]
+    fn try_cast_clipped(self, r: RangeInclusive<O>) -> Result<O, CastError> {
+        let (start, end) = r.into_inner();
+        match start.partial_cmp(&end) {
+            Some(Ordering::Less) | Some(Ordering::Equal) => {}
+            _ => return Err(CastError::EmptyRange)
+        }
+        let v = self.try_cast()?;
+        if v < start {
+            Err(CastError::TooSmall)
+        } else if v > end {
+            Err(CastError::TooLarge)
+        } else {
+            Ok(v)
+        }
+    }
+    #[inline]
+    fn try_cast_exact(self) -> Result<O, CastError> {
+        match self.cast_exact() {
+            Some(v) => Ok(v),
+            // NaN converts between floats, but never exactly
+            None if is_nan(self) => Err(CastError::NaN),
+            None => self.try_cast().and(Err(CastError::InexactFraction))
+        }
+    }
+}
+
+macro_rules! impl_try_cast {
+    ($($Tuple:ident $Arr:ident { $($T:ident . $t:ident . $idx:tt),* } )*) => ($(
+        #[allow(non_camel_case_types)]
+        impl<$($T, $t),*> TryCast<$Tuple<$($t),*>> for $Tuple<$($T),*>
+        where $( $T: TryCast<$t> ),*
+        {
+            #[inline]
+            fn try_cast(self) -> Result<$Tuple<$($t),*>, CastError> {
+                Ok($Tuple( $(
+                    self.$idx.try_cast().map_err(|e| CastError::Component($idx, Box::new(e)))?
+                ),* ))
+            }
+            #[inline]
+            fn try_cast_clipped(self, r: RangeInclusive<$Tuple<$($t),*>>) -> Result<$Tuple<$($t),*>, CastError> {
+                let (start, end) = r.into_inner();
+                Ok($Tuple( $(
+                    self.$idx.try_cast_clipped(start.$idx ..= end.$idx)
+                        .map_err(|e| CastError::Component($idx, Box::new(e)))?
+                ),* ))
+            }
+            #[inline]
+            fn try_cast_exact(self) -> Result<$Tuple<$($t),*>, CastError> {
+                Ok($Tuple( $(
+                    self.$idx.try_cast_exact().map_err(|e| CastError::Component($idx, Box::new(e)))?
+                ),* ))
+            }
+        }
+    )*)
+}
+impl_tuple!(impl_try_cast);
+
+#[test]
+fn test_try_cast() {
+    assert_eq!(TryCast::<u8>::try_cast(300i32), Err(CastError::TooLarge));
+    assert_eq!(TryCast::<u8>::try_cast(-3i32), Err(CastError::TooSmall));
+    assert_eq!(TryCast::<i32>::try_cast(f64::NAN), Err(CastError::NaN));
+    assert_eq!(TryCast::<u8>::try_cast(200u16), Ok(200));
+    assert_eq!(TryCast::<i32>::try_cast_exact(2.5f64), Err(CastError::InexactFraction));
+    assert_eq!(TryCast::<i32>::try_cast_exact(3e10f64), Err(CastError::TooLarge));
+    assert_eq!(TryCast::<f32>::try_cast_exact(u64::MAX), Err(CastError::InexactFraction));
+    assert_eq!(TryCast::<f64>::try_cast_exact(f32::NAN), Err(CastError::NaN));
+    assert_eq!(TryCast::<f32>::try_cast_exact(f64::NAN), Err(CastError::NaN));
+    assert_eq!(TryCast::<i32>::try_cast_exact(f32::NAN), Err(CastError::NaN));
+    let (lo, hi) = (5u8, 1);
+    assert_eq!(7u16.try_cast_clipped(lo ..= hi), Err(CastError::EmptyRange));
+    assert_eq!(1.5f32.try_cast_clipped(f64::NAN ..= 2.), Err(CastError::EmptyRange));
+    assert_eq!(7u16.try_cast_clipped(0u8 ..= 5), Err(CastError::TooLarge));
+    assert_eq!(3u16.try_cast_clipped(0u8 ..= 5), Ok(3));
+    assert_eq!(T2(1i32, -1i32).try_cast(), Err::<T2<u8, u8>, _>(CastError::Component(1, Box::new(CastError::TooSmall))));
+    assert_eq!(format!("{}", CastError::Component(1, Box::new(CastError::NaN))), "component 1: value is NaN");
+}
+
+// every integer pair must exist and agree with TryFrom on the boundaries of all types
+#[test]
+fn test_cast_matrix() {
+    const SAMPLES: &[i128] = &[
+        0, 1, -1, 2, 100, -100,
+        127, 128, -128, -129, 255, 256,
+        32767, 32768, -32768, -32769, 65535, 65536,
+        2147483647, 2147483648, -2147483648, -2147483649, 4294967295, 4294967296,
+        9223372036854775807, 9223372036854775808, -9223372036854775808, -9223372036854775809,
+        18446744073709551615, 18446744073709551616,
+        170141183460469231731687303715884105727, -170141183460469231731687303715884105728,
+    ];
+    macro_rules! check {
+        ([$($src:ident),*] => $dst:tt) => ( $( check!(@ $src => $dst); )* );
+        (@ $src:ident => [$($dst:ident),*]) => ( $(
+            let extra = [$src::MIN, $src::MAX];
+            let samples = SAMPLES.iter().filter_map(|&v| $src::try_from(v).ok()).chain(extra.iter().cloned());
+            for x in samples {
+                assert_eq!(Cast::<$dst>::cast(x), $dst::try_from(x).ok(),
+                    "{}({}) as {}", stringify!($src), x, stringify!($dst));
+                let clamped = $dst::try_from(x).unwrap_or(if x > 0 { $dst::MAX } else { $dst::MIN });
+                assert_eq!(Cast::<$dst>::cast_clamping(x), clamped,
+                    "{}({}) clamping to {}", stringify!($src), x, stringify!($dst));
+            }
+        )* );
+    }
+    check!([u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize]
+        => [u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize]);
+
+    macro_rules! check_float {
+        ($($src:ident),* => $dst:tt) => ( $( check_float!(@ $src => $dst); )* );
+        (@ $src:ident => [$($dst:ident),*]) => ( $(
+            assert_eq!(Cast::<$dst>::cast(100 as $src), Some(100));
+            assert_eq!(Cast::<$dst>::cast(0.5 as $src), Some(0));
+        )* );
+    }
+    check_float!(f32, f64 => [u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize]);
+
+    for &b in &[false, true] {
+        assert_eq!(Cast::<i8>::cast(b), Some(b as i8));
+        assert_eq!(Cast::<f64>::cast(b), Some(b as u8 as f64));
+    }
+    for &c in &['a', '\u{ff}', '\u{100}', '\u{10ffff}'] {
+        assert_eq!(Cast::<u8>::cast(c), u8::try_from(u32::from(c)).ok());
+        assert_eq!(Cast::<i16>::cast(c), i16::try_from(u32::from(c)).ok());
+        assert_eq!(Cast::<f32>::cast(c), Some(u32::from(c) as f32));
+    }
+
+    assert_eq!(Cast::<f32>::cast(u128::MAX), None);
+    assert_eq!(Cast::<f32>::cast_clamping(u128::MAX), f32::MAX);
+    assert_eq!(Cast::<f32>::cast(1u128 << 100), Some(2f32.powi(100)));
+    assert_eq!(Cast::<f64>::cast(u128::MAX), Some(u128::MAX as f64));
 }
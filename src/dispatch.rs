@@ -0,0 +1,196 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::cmp::min;
+use real::Real;
+use kernel;
+use simd::Dispatched;
+use simd_::{f32x4, f64x2, f32x8, f64x4};
+
+/// Instruction set levels, from least to most capable.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Scalar,
+    Sse41,
+    Avx,
+    /// AVX2 and FMA
+    Avx2,
+}
+
+const LEVELS: [Level; 4] = [Level::Scalar, Level::Sse41, Level::Avx, Level::Avx2];
+
+// 0: no limit, otherwise the index of the forced level + 1
+static FORCED: AtomicUsize = AtomicUsize::new(0);
+
+/// The best level the running cpu supports.
+pub fn detect() -> Level {
+    if is_x86_feature_detected!("avx2") && is_x86_feature_detected!("fma") {
+        Level::Avx2
+    } else if is_x86_feature_detected!("avx") {
+        Level::Avx
+    } else if is_x86_feature_detected!("sse4.1") {
+        Level::Sse41
+    } else {
+        Level::Scalar
+    }
+}
+
+/// Limit the dispatch to `level`, or go back to the detected level with `None`.
+/// Levels the cpu does not support are never used.
+pub fn force_level(level: Option<Level>) {
+    let v = match level {
+        Some(l) => LEVELS.iter().position(|&m| m == l).unwrap() + 1,
+        None => 0
+    };
+    FORCED.store(v, Ordering::Relaxed);
+}
+
+/// The level used by the slice drivers.
+pub fn level() -> Level {
+    match FORCED.load(Ordering::Relaxed) {
+        0 => detect(),
+        n => min(LEVELS[n - 1], detect())
+    }
+}
+
+/// Scalar types with vector implementations for each level.
+///
+/// The vector types of the levels are private, they may only run after checking the cpu.
+pub trait Dispatch: Real<Scalar=Self> {
+    #[doc(hidden)]
+    fn dispatch_map<K: Kernel<Self>>(input: &[Self], out: &mut [Self], k: &K);
+    #[doc(hidden)]
+    fn dispatch_zip<K: Kernel2<Self>>(a: &[Self], b: &[Self], out: &mut [Self], k: &K);
+}
+
+macro_rules! impl_dispatch {
+    ($($scalar:ident: $v128:ident, $v256:ident;)*) => ($(
+        impl Dispatch for $scalar {
+            fn dispatch_map<K: Kernel<Self>>(input: &[Self], out: &mut [Self], k: &K) {
+                match level() {
+                    Level::Avx2 => unsafe { map_slice_avx2::<Dispatched<$v256, true>, K>(input, out, k) },
+                    Level::Avx => unsafe { map_slice_avx::<Dispatched<$v256, false>, K>(input, out, k) },
+                    Level::Sse41 => unsafe { map_slice_sse41::<Dispatched<$v128, false>, K>(input, out, k) },
+                    Level::Scalar => kernel::map_slice(input, out, |x: Self| k.apply(x)),
+                }
+            }
+            fn dispatch_zip<K: Kernel2<Self>>(a: &[Self], b: &[Self], out: &mut [Self], k: &K) {
+                match level() {
+                    Level::Avx2 => unsafe { zip_map_avx2::<Dispatched<$v256, true>, K>(a, b, out, k) },
+                    Level::Avx => unsafe { zip_map_avx::<Dispatched<$v256, false>, K>(a, b, out, k) },
+                    Level::Sse41 => unsafe { zip_map_sse41::<Dispatched<$v128, false>, K>(a, b, out, k) },
+                    Level::Scalar => kernel::zip_map(a, b, out, |x: Self, y: Self| k.apply(x, y)),
+                }
+            }
+        }
+    )*)
+}
+impl_dispatch!(
+    f32: f32x4, f32x8;
+    f64: f64x2, f64x4;
+);
+
+/// A kernel that works for every Real with scalar type S.
+/// (closures can not be generic, so the kernel is passed as a type.)
+pub trait Kernel<S> {
+    fn apply<R: Real<Scalar=S>>(&self, x: R) -> R;
+}
+
+/// A kernel taking two arguments, see `Kernel`.
+pub trait Kernel2<S> {
+    fn apply<R: Real<Scalar=S>>(&self, a: R, b: R) -> R;
+}
+
+/// `kernel::map_slice` using the widest implementation the cpu supports.
+pub fn map_slice<S: Dispatch, K: Kernel<S>>(input: &[S], out: &mut [S], k: &K) {
+    S::dispatch_map(input, out, k)
+}
+
+#[target_feature(enable = "avx2,fma")]
+unsafe fn map_slice_avx2<V: Real, K: Kernel<V::Scalar>>(input: &[V::Scalar], out: &mut [V::Scalar], k: &K) {
+    kernel::map_slice(input, out, |x: V| k.apply(x))
+}
+
+#[target_feature(enable = "avx")]
+unsafe fn map_slice_avx<V: Real, K: Kernel<V::Scalar>>(input: &[V::Scalar], out: &mut [V::Scalar], k: &K) {
+    kernel::map_slice(input, out, |x: V| k.apply(x))
+}
+
+#[target_feature(enable = "sse4.1")]
+unsafe fn map_slice_sse41<V: Real, K: Kernel<V::Scalar>>(input: &[V::Scalar], out: &mut [V::Scalar], k: &K) {
+    kernel::map_slice(input, out, |x: V| k.apply(x))
+}
+
+/// `kernel::zip_map` using the widest implementation the cpu supports.
+pub fn zip_map<S: Dispatch, K: Kernel2<S>>(a: &[S], b: &[S], out: &mut [S], k: &K) {
+    S::dispatch_zip(a, b, out, k)
+}
+
+#[target_feature(enable = "avx2,fma")]
+unsafe fn zip_map_avx2<V: Real, K: Kernel2<V::Scalar>>(a: &[V::Scalar], b: &[V::Scalar], out: &mut [V::Scalar], k: &K) {
+    kernel::zip_map(a, b, out, |x: V, y: V| k.apply(x, y))
+}
+
+#[target_feature(enable = "avx")]
+unsafe fn zip_map_avx<V: Real, K: Kernel2<V::Scalar>>(a: &[V::Scalar], b: &[V::Scalar], out: &mut [V::Scalar], k: &K) {
+    kernel::zip_map(a, b, out, |x: V, y: V| k.apply(x, y))
+}
+
+#[target_feature(enable = "sse4.1")]
+unsafe fn zip_map_sse41<V: Real, K: Kernel2<V::Scalar>>(a: &[V::Scalar], b: &[V::Scalar], out: &mut [V::Scalar], k: &K) {
+    kernel::zip_map(a, b, out, |x: V, y: V| k.apply(x, y))
+}
+
+// the tests force levels, so they must not run at the same time
+#[cfg(test)]
+static FORCE_LOCK: ::std::sync::Mutex<()> = ::std::sync::Mutex::new(());
+
+#[test]
+fn test_levels_agree() {
+    struct Poly;
+    impl Kernel<f32> for Poly {
+        fn apply<R: Real<Scalar=f32>>(&self, x: R) -> R {
+            x.mul_add(x, R::int(3)).sqrt() + x.floor() - x.round().max(R::int(2))
+        }
+    }
+
+    let _lock = FORCE_LOCK.lock().unwrap();
+    let input: Vec<f32> = (0 .. 37).map(|i| i as f32 * 0.25).collect();
+    let mut reference = vec![0.; input.len()];
+    force_level(Some(Level::Scalar));
+    map_slice(&input, &mut reference, &Poly);
+
+    for &l in &LEVELS[1 ..] {
+        let mut out = vec![0.; input.len()];
+        force_level(Some(l));
+        map_slice(&input, &mut out, &Poly);
+        assert_eq!(out, reference);
+    }
+    force_level(None);
+}
+
+#[test]
+fn test_level_lanes() {
+    use std::cell::Cell;
+    // records the widest vector the kernel is called with
+    struct Lanes(Cell<usize>);
+    impl Kernel<f32> for Lanes {
+        fn apply<R: Real<Scalar=f32>>(&self, x: R) -> R {
+            self.0.set(self.0.get().max(R::LANES));
+            x
+        }
+    }
+
+    let _lock = FORCE_LOCK.lock().unwrap();
+    let input = [1f32; 20];
+    let mut out = [0f32; 20];
+    for &(l, lanes) in &[(Level::Scalar, 1), (Level::Sse41, 4), (Level::Avx, 8), (Level::Avx2, 8)] {
+        if l > detect() {
+            continue;
+        }
+        let k = Lanes(Cell::new(0));
+        force_level(Some(l));
+        map_slice(&input, &mut out, &k);
+        assert_eq!(k.0.get(), lanes);
+        assert_eq!(out, input);
+    }
+    force_level(None);
+}
@@ -0,0 +1,64 @@
+use real::Real;
+
+/// Apply f to every value of input and store the results in out.
+///
+/// The buffers are processed R::LANES values at once. A remainder that does
+/// not fill a whole R is padded with its first value, and only the valid
+/// lanes are written back.
+/// panics if input and out differ in length.
+#[inline]
+pub fn map_slice<R, F>(input: &[R::Scalar], out: &mut [R::Scalar], f: F)
+    where R: Real, F: Fn(R) -> R
+{
+    assert_eq!(input.len(), out.len());
+    let full = input.len() - input.len() % R::LANES;
+    let (input, input_tail) = input.split_at(full);
+    let (out, out_tail) = out.split_at_mut(full);
+
+    for (i, o) in input.chunks(R::LANES).zip(out.chunks_mut(R::LANES)) {
+        f(R::from_slice(i)).write_to_slice(o);
+    }
+    if let Some(&pad) = input_tail.first() {
+        f(R::from_slice_padded(input_tail, pad)).write_to_slice_partial(out_tail);
+    }
+}
+
+/// Apply f to every pair of values from a and b and store the results in out.
+///
+/// Works like `map_slice`.
+/// panics if a, b and out differ in length.
+#[inline]
+pub fn zip_map<R, F>(a: &[R::Scalar], b: &[R::Scalar], out: &mut [R::Scalar], f: F)
+    where R: Real, F: Fn(R, R) -> R
+{
+    assert_eq!(a.len(), out.len());
+    assert_eq!(b.len(), out.len());
+    let full = out.len() - out.len() % R::LANES;
+    let (a, a_tail) = a.split_at(full);
+    let (b, b_tail) = b.split_at(full);
+    let (out, out_tail) = out.split_at_mut(full);
+
+    for ((a, b), o) in a.chunks(R::LANES).zip(b.chunks(R::LANES)).zip(out.chunks_mut(R::LANES)) {
+        f(R::from_slice(a), R::from_slice(b)).write_to_slice(o);
+    }
+    if let (Some(&pad_a), Some(&pad_b)) = (a_tail.first(), b_tail.first()) {
+        let r = f(R::from_slice_padded(a_tail, pad_a), R::from_slice_padded(b_tail, pad_b));
+        r.write_to_slice_partial(out_tail);
+    }
+}
+
+#[test]
+fn test_map_slice() {
+    use tuple::T4;
+    type V = T4<f64, f64, f64, f64>;
+
+    let a: Vec<f64> = (0 .. 11).map(|i| i as f64).collect();
+    let b: Vec<f64> = (0 .. 11).map(|i| 2. * i as f64).collect();
+    let mut out = vec![0.; 11];
+
+    map_slice(&a, &mut out, |x: V| x * x);
+    assert!(out.iter().enumerate().all(|(i, &v)| v == (i * i) as f64));
+
+    zip_map(&a, &b, &mut out, |x: V, y: V| x + y);
+    assert!(out.iter().enumerate().all(|(i, &v)| v == (3 * i) as f64));
+}
@@ -1,12 +1,28 @@
-#![feature(core_intrinsics)]
-#![feature(const_fn)]
-#![feature(concat_idents)]
-#![cfg_attr(feature="simd", feature(stdsimd))]
+//! Traits to simplify vectorized mathematics.
+//!
+//! `Real` is implemented for `f32`, `f64`, tuples of `Real`s and arrays of `Real`s
+//! (through the `Array` wrapper, which converts from and into `[T; N]`).
+//!
+//! # Features
+//! - `simd`: implement `Real` for the `packed_simd` types `f32x4`, `f64x2`, `f32x8` and `f64x4`
+//!   and add the `dispatch` module. `packed_simd` needs a nightly compiler.
+//!   The vector implementations use SSE4.1 and AVX instructions only if they are enabled
+//!   at compile time (`-C target-feature=+sse4.1,+avx`) and portable code otherwise.
+//!   `dispatch` checks the cpu at runtime and uses SSE4.1, AVX or AVX2 with FMA.
+//! - `impl_simd`: old name of `simd`.
+//! - `portable_simd`: implement `Real` for `std::simd::Simd<f32, N>` and `Simd<f64, N>`
+//!   with N = 2, 4, 8 and 16. Works on every target, but needs a nightly compiler.
+//! - `derive`: `#[derive(Cast)]` for structs, converting field by field.
+
+#![cfg_attr(feature="portable_simd", feature(portable_simd))]
 
 extern crate rand;
 extern crate tuple;
 #[cfg(feature="simd")]
+#[macro_use]
 extern crate packed_simd as simd_;
+#[cfg(feature="derive")]
+extern crate math_traits_derive;
 
 macro_rules! first_t {
     ($A:ty, $B:tt) => ($A)
@@ -19,9 +35,23 @@ macro_rules! first_e {
 }
 
 pub mod real;
+pub mod mask;
 pub mod cast;
+pub mod policy;
+pub mod array;
+pub mod kernel;
+#[cfg(any(feature="simd", feature="portable_simd"))]
+mod approx;
 #[cfg(feature="simd")]
 pub mod simd;
+#[cfg(all(feature="simd", target_arch="x86_64"))]
+pub mod dispatch;
+#[cfg(feature="portable_simd")]
+pub mod portable;
 
 pub use real::Real;
+pub use mask::Mask;
+pub use array::Array;
 pub use cast::*;
+#[cfg(feature="derive")]
+pub use math_traits_derive::Cast;
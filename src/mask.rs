@@ -0,0 +1,169 @@
+use tuple::*;
+
+/// The result of a lane-wise comparison (`Real::Bool`).
+pub trait Mask: Sized + Copy {
+    /// a mask with every lane set to b, for use with `Real::select`
+    fn splat(b: bool) -> Self;
+
+    /// a & b
+    fn and(self, rhs: Self) -> Self;
+
+    /// a | b
+    fn or(self, rhs: Self) -> Self;
+
+    /// a ^ b
+    fn xor(self, rhs: Self) -> Self;
+
+    /// !a
+    fn not(self) -> Self;
+
+    /// true if at least one lane is set
+    fn any(self) -> bool;
+
+    /// true if every lane is set
+    fn all(self) -> bool;
+
+    /// true if no lane is set
+    #[inline]
+    fn none(self) -> bool {
+        !self.any()
+    }
+
+    /// number of lanes that are set
+    fn count_true(self) -> usize;
+}
+
+impl Mask for bool {
+    #[inline(always)]
+    fn splat(b: bool) -> Self { b }
+
+    #[inline(always)]
+    fn and(self, rhs: Self) -> Self { self & rhs }
+
+    #[inline(always)]
+    fn or(self, rhs: Self) -> Self { self | rhs }
+
+    #[inline(always)]
+    fn xor(self, rhs: Self) -> Self { self ^ rhs }
+
+    #[inline(always)]
+    fn not(self) -> Self { !self }
+
+    #[inline(always)]
+    fn any(self) -> bool { self }
+
+    #[inline(always)]
+    fn all(self) -> bool { self }
+
+    #[inline(always)]
+    fn count_true(self) -> usize { self as usize }
+}
+
+macro_rules! impl_mask {
+    ($($Tuple:ident $Arr:ident { $($T:ident . $t:ident . $idx:tt),* } )*) => ($(
+        impl<M: Mask> Mask for $Tuple<$(first_i!(M, $T),)*> {
+            #[inline]
+            fn splat(b: bool) -> Self {
+                $Tuple( $(first_e!(M::splat(b), $idx),)* )
+            }
+            #[inline]
+            fn and(self, rhs: Self) -> Self {
+                $Tuple( $(M::and(self.$idx, rhs.$idx),)* )
+            }
+            #[inline]
+            fn or(self, rhs: Self) -> Self {
+                $Tuple( $(M::or(self.$idx, rhs.$idx),)* )
+            }
+            #[inline]
+            fn xor(self, rhs: Self) -> Self {
+                $Tuple( $(M::xor(self.$idx, rhs.$idx),)* )
+            }
+            #[inline]
+            fn not(self) -> Self {
+                $Tuple( $(M::not(self.$idx),)* )
+            }
+            #[inline]
+            fn any(self) -> bool {
+                false $(|| M::any(self.$idx))*
+            }
+            #[inline]
+            fn all(self) -> bool {
+                true $(&& M::all(self.$idx))*
+            }
+            #[inline]
+            fn count_true(self) -> usize {
+                0 $(+ M::count_true(self.$idx))*
+            }
+        }
+    )*)
+}
+impl_tuple!(impl_mask);
+
+impl<M: Mask, const N: usize> Mask for [M; N] {
+    #[inline]
+    fn splat(b: bool) -> Self {
+        [M::splat(b); N]
+    }
+    #[inline]
+    fn and(self, rhs: Self) -> Self {
+        ::std::array::from_fn(|i| M::and(self[i], rhs[i]))
+    }
+    #[inline]
+    fn or(self, rhs: Self) -> Self {
+        ::std::array::from_fn(|i| M::or(self[i], rhs[i]))
+    }
+    #[inline]
+    fn xor(self, rhs: Self) -> Self {
+        ::std::array::from_fn(|i| M::xor(self[i], rhs[i]))
+    }
+    #[inline]
+    fn not(self) -> Self {
+        self.map(M::not)
+    }
+    #[inline]
+    fn any(self) -> bool {
+        self.iter().any(|&m| M::any(m))
+    }
+    #[inline]
+    fn all(self) -> bool {
+        self.iter().all(|&m| M::all(m))
+    }
+    #[inline]
+    fn count_true(self) -> usize {
+        self.iter().map(|&m| M::count_true(m)).sum()
+    }
+}
+
+#[test]
+fn test_mask_bool() {
+    for &a in &[false, true] {
+        for &b in &[false, true] {
+            assert_eq!(a.and(b), a && b);
+            assert_eq!(a.or(b), a || b);
+            assert_eq!(a.xor(b), a != b);
+        }
+        assert_eq!(a.not(), !a);
+        assert_eq!((a.any(), a.all(), a.none()), (a, a, !a));
+        assert_eq!(a.count_true(), a as usize);
+        assert_eq!(<bool as Mask>::splat(a), a);
+    }
+}
+
+#[test]
+fn test_mask_tuple() {
+    use real::Real;
+    let a = T4(1.0f64, -2.0, 3.0, f64::NAN);
+    let pos = a.gt(T4::int(0));
+    let small = a.lt(T4::int(2));
+    assert_eq!(pos, T4(true, false, true, false));
+    assert_eq!(pos.and(small), T4(true, false, false, false));
+    assert_eq!(pos.or(small), T4(true, true, true, false));
+    assert_eq!(pos.xor(small), T4(false, true, true, false));
+    assert_eq!(pos.not(), T4(false, true, false, true));
+    assert!(pos.any() && !pos.all() && !pos.none());
+    assert!(a.is_nan().any() && a.eq(a).not().any());
+    assert_eq!(pos.count_true(), 2);
+    assert_eq!(pos.or(small).count_true(), 3);
+    assert!(<T4<bool, bool, bool, bool> as Mask>::splat(true).all());
+    assert!(<T4<bool, bool, bool, bool> as Mask>::splat(false).none());
+}
@@ -0,0 +1,93 @@
+//! Overflow policies as types, so code can be generic over how a cast handles
+//! values that do not fit.
+//!
+//! ```
+//! use math_traits::policy::{CastWith, Policy, Saturate, Wrap};
+//!
+//! fn decode<P: Policy<i32, u8>>(samples: &[i32]) -> Vec<P::Output> {
+//!     samples.iter().map(|&s| s.cast_with::<P, u8>()).collect()
+//! }
+//! assert_eq!(decode::<Saturate>(&[-1, 300]), vec![0, 255]);
+//! assert_eq!(decode::<Wrap>(&[-1, 300]), vec![255, 44]);
+//! ```
+use cast::{Cast, CastExact, CastWrapping};
+
+/// How a value of T is converted to O.
+pub trait Policy<T, O> {
+    /// O, or Option<O> for the policies that can fail
+    type Output;
+
+    fn apply(x: T) -> Self::Output;
+}
+
+/// Clamp to the range of O, see `Cast::cast_clamping`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Saturate;
+
+/// Wrap around, see `CastWrapping`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Wrap;
+
+/// None if out of range, see `Cast::cast`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Checked;
+
+/// None unless the value is represented exactly, see `CastExact`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Exact;
+
+impl<T: Cast<O>, O> Policy<T, O> for Saturate {
+    type Output = O;
+
+    #[inline(always)]
+    fn apply(x: T) -> O {
+        x.cast_clamping()
+    }
+}
+
+impl<T: CastWrapping<O>, O> Policy<T, O> for Wrap {
+    type Output = O;
+
+    #[inline(always)]
+    fn apply(x: T) -> O {
+        x.cast_wrapping()
+    }
+}
+
+impl<T: Cast<O>, O> Policy<T, O> for Checked {
+    type Output = Option<O>;
+
+    #[inline(always)]
+    fn apply(x: T) -> Option<O> {
+        x.cast()
+    }
+}
+
+impl<T: CastExact<O>, O> Policy<T, O> for Exact {
+    type Output = Option<O>;
+
+    #[inline(always)]
+    fn apply(x: T) -> Option<O> {
+        x.cast_exact()
+    }
+}
+
+/// `x.cast_with::<P, O>()` converts x to O with the policy P.
+pub trait CastWith: Sized {
+    #[inline(always)]
+    fn cast_with<P: Policy<Self, O>, O>(self) -> P::Output {
+        P::apply(self)
+    }
+}
+
+impl<T> CastWith for T {}
+
+#[test]
+fn test_policies() {
+    assert_eq!(300.5f32.cast_with::<Saturate, u8>(), 255);
+    assert_eq!(300.5f32.cast_with::<Wrap, u8>(), 44);
+    assert_eq!(300.5f32.cast_with::<Checked, u8>(), None);
+    assert_eq!(200.5f32.cast_with::<Checked, u8>(), Some(200));
+    assert_eq!(200.5f32.cast_with::<Exact, u8>(), None);
+    assert_eq!(200f32.cast_with::<Exact, u8>(), Some(200));
+}
@@ -0,0 +1,321 @@
+use std::simd::{Simd, Select, StdFloat};
+use std::simd::Mask as SimdMask;
+use std::simd::prelude::{SimdFloat, SimdPartialEq, SimdPartialOrd, SimdInt, SimdUint};
+use rand::Rng;
+use real::Real;
+use mask::Mask;
+use approx::{self, Exponent};
+
+macro_rules! impl_portable {
+    ($scalar:ident: $bits:ident, $int:ident; $($n:tt),*) => ( $(
+        impl Real for Simd<$scalar, $n> {
+            const PI: Self = Simd::from_array([::std::$scalar::consts::PI; $n]);
+            const E: Self = Simd::from_array([::std::$scalar::consts::E; $n]);
+            const TAU: Self = Simd::from_array([::std::$scalar::consts::TAU; $n]);
+            const FRAC_PI_2: Self = Simd::from_array([::std::$scalar::consts::FRAC_PI_2; $n]);
+            const LN_2: Self = Simd::from_array([::std::$scalar::consts::LN_2; $n]);
+            const LN_10: Self = Simd::from_array([::std::$scalar::consts::LN_10; $n]);
+            const SQRT_2: Self = Simd::from_array([::std::$scalar::consts::SQRT_2; $n]);
+            const EPSILON: Self = Simd::from_array([$scalar::EPSILON; $n]);
+            const INFINITY: Self = Simd::from_array([$scalar::INFINITY; $n]);
+            const NEG_INFINITY: Self = Simd::from_array([$scalar::NEG_INFINITY; $n]);
+            const NAN: Self = Simd::from_array([$scalar::NAN; $n]);
+            const MIN_POSITIVE: Self = Simd::from_array([$scalar::MIN_POSITIVE; $n]);
+            const MAX: Self = Simd::from_array([$scalar::MAX; $n]);
+            const MIN: Self = Simd::from_array([$scalar::MIN; $n]);
+            const LANES: usize = $n;
+            type Bool = SimdMask<$int, $n>;
+            type Scalar = $scalar;
+            type Iterator = ::std::array::IntoIter<$scalar, $n>;
+
+            #[inline(always)]
+            fn splat(s: Self::Scalar) -> Self {
+                Simd::splat(s)
+            }
+
+            #[inline(always)]
+            fn values(self) -> Self::Iterator {
+                IntoIterator::into_iter(self.to_array())
+            }
+
+            #[inline(always)]
+            fn from_slice(s: &[Self::Scalar]) -> Self {
+                Simd::from_slice(s)
+            }
+
+            #[inline(always)]
+            fn from_slice_padded(s: &[Self::Scalar], pad: Self::Scalar) -> Self {
+                Simd::load_or(s, Simd::splat(pad))
+            }
+
+            #[inline(always)]
+            fn write_to_slice(self, s: &mut [Self::Scalar]) {
+                self.copy_to_slice(s)
+            }
+
+            #[inline(always)]
+            fn write_to_slice_partial(self, s: &mut [Self::Scalar]) {
+                let arr = self.to_array();
+                let n = s.len().min($n);
+                s[.. n].copy_from_slice(&arr[.. n]);
+            }
+
+            #[inline(always)]
+            fn reduce_sum(self) -> Self::Scalar { SimdFloat::reduce_sum(self) }
+
+            #[inline(always)]
+            fn reduce_product(self) -> Self::Scalar { SimdFloat::reduce_product(self) }
+
+            #[inline(always)]
+            fn reduce_min(self) -> Self::Scalar { SimdFloat::reduce_min(self) }
+
+            #[inline(always)]
+            fn reduce_max(self) -> Self::Scalar { SimdFloat::reduce_max(self) }
+
+            #[inline(always)]
+            fn int(v: i16) -> Self { Self::splat($scalar::from(v)) }
+
+            #[inline]
+            fn float(f: f64) -> Self {
+                Self::splat(f as $scalar)
+            }
+
+            #[inline]
+            fn try_float(f: f64) -> Option<Self> {
+                <$scalar as Real>::try_float(f).map(Self::splat)
+            }
+
+            #[inline]
+            fn float_saturating(f: f64) -> Self {
+                Self::splat(<$scalar as Real>::float_saturating(f))
+            }
+
+            #[inline]
+            fn frac(nom: i16, denom: u16) -> Self {
+                Self::splat($scalar::from(nom) / $scalar::from(denom))
+            }
+
+            #[inline]
+            fn from_i32(v: i32) -> Self { Self::splat(<$scalar as Real>::from_i32(v)) }
+
+            #[inline]
+            fn from_i64(v: i64) -> Self { Self::splat(<$scalar as Real>::from_i64(v)) }
+
+            #[inline]
+            fn from_u32(v: u32) -> Self { Self::splat(<$scalar as Real>::from_u32(v)) }
+
+            #[inline]
+            fn from_ratio(nom: i64, denom: u64) -> Self { Self::splat(<$scalar as Real>::from_ratio(nom, denom)) }
+
+            #[inline(always)]
+            fn wrap(self, at: Self, span: Self) -> Self {
+                Real::select(self - span, self, self.simd_gt(at))
+            }
+
+            #[inline]
+            fn uniform01<R: Rng>(rng: &mut R) -> Self {
+                Simd::from_array(rng.gen())
+            }
+
+            #[cfg(target_feature="fma")]
+            #[inline(always)]
+            fn mul_add(self, b: Self, c: Self) -> Self {
+                StdFloat::mul_add(self, b, c)
+            }
+
+            #[inline(always)]
+            fn abs(self) -> Self { SimdFloat::abs(self) }
+
+            #[inline(always)]
+            fn sqrt(self) -> Self { StdFloat::sqrt(self) }
+
+            #[inline(always)]
+            fn sin(self) -> Self { approx::sin(self) }
+
+            #[inline(always)]
+            fn cos(self) -> Self { approx::cos(self) }
+
+            #[inline(always)]
+            fn exp(self) -> Self { approx::exp(self) }
+
+            #[inline(always)]
+            fn ln(self) -> Self { approx::ln(self) }
+
+            #[inline(always)]
+            fn powf(self, e: Self) -> Self { approx::powf(self, e) }
+
+            #[inline(always)]
+            fn exp2(self) -> Self { approx::exp2(self) }
+
+            #[inline(always)]
+            fn log2(self) -> Self { approx::log2(self) }
+
+            #[inline(always)]
+            fn floor(self) -> Self { StdFloat::floor(self) }
+
+            #[inline(always)]
+            fn ceil(self) -> Self { StdFloat::ceil(self) }
+
+            #[inline(always)]
+            fn round(self) -> Self { StdFloat::round(self) }
+
+            #[inline(always)]
+            fn trunc(self) -> Self { StdFloat::trunc(self) }
+
+            #[inline(always)]
+            fn fract(self) -> Self { StdFloat::fract(self) }
+
+            #[inline(always)]
+            fn signum(self) -> Self { SimdFloat::signum(self) }
+
+            #[inline(always)]
+            fn copysign(self, sign: Self) -> Self { SimdFloat::copysign(self, sign) }
+
+            #[inline(always)]
+            fn is_nan(self) -> Self::Bool { SimdFloat::is_nan(self) }
+
+            #[inline(always)]
+            fn is_finite(self) -> Self::Bool { SimdFloat::is_finite(self) }
+
+            #[inline(always)]
+            fn is_infinite(self) -> Self::Bool { SimdFloat::is_infinite(self) }
+
+            #[inline(always)]
+            fn is_sign_negative(self) -> Self::Bool { SimdFloat::is_sign_negative(self) }
+
+            #[inline(always)]
+            fn min(self, other: Self) -> Self { self.simd_min(other) }
+
+            #[inline(always)]
+            fn max(self, other: Self) -> Self { self.simd_max(other) }
+
+            #[inline(always)]
+            fn lt(self, rhs: Self) -> Self::Bool { self.simd_lt(rhs) }
+
+            #[inline(always)]
+            fn le(self, rhs: Self) -> Self::Bool { self.simd_le(rhs) }
+
+            #[inline(always)]
+            fn gt(self, rhs: Self) -> Self::Bool { self.simd_gt(rhs) }
+
+            #[inline(always)]
+            fn ge(self, rhs: Self) -> Self::Bool { self.simd_ge(rhs) }
+
+            #[inline(always)]
+            fn eq(self, rhs: Self) -> Self::Bool { self.simd_eq(rhs) }
+
+            #[inline(always)]
+            fn select(self, other: Self, cond: Self::Bool) -> Self {
+                cond.select(self, other)
+            }
+        }
+
+        impl Mask for SimdMask<$int, $n> {
+            #[inline(always)]
+            fn splat(b: bool) -> Self { SimdMask::splat(b) }
+
+            #[inline(always)]
+            fn and(self, rhs: Self) -> Self { self & rhs }
+
+            #[inline(always)]
+            fn or(self, rhs: Self) -> Self { self | rhs }
+
+            #[inline(always)]
+            fn xor(self, rhs: Self) -> Self { self ^ rhs }
+
+            #[inline(always)]
+            fn not(self) -> Self { !self }
+
+            #[inline(always)]
+            fn any(self) -> bool { SimdMask::any(self) }
+
+            #[inline(always)]
+            fn all(self) -> bool { SimdMask::all(self) }
+
+            #[inline(always)]
+            fn count_true(self) -> usize { self.to_bitmask().count_ones() as usize }
+        }
+
+        impl Exponent for Simd<$scalar, $n> {
+            const LDEXP_MAX: i16 = 2 * ($scalar::MAX_EXP as i16 - 2);
+
+            #[inline(always)]
+            fn ldexp(self, n: Self) -> Self {
+                // n has to be integral and within the normal exponent range
+                #[inline(always)]
+                fn pow2(n: Simd<$scalar, $n>) -> Simd<$scalar, $n> {
+                    let bias = $scalar::MAX_EXP as $int - 1;
+                    let bits = (n.cast::<$int>() + Simd::splat(bias)) << ($scalar::MANTISSA_DIGITS as $int - 1);
+                    Simd::from_bits(bits.cast::<$bits>())
+                }
+                let max = Self::splat(Self::LDEXP_MAX as $scalar);
+                let n = n.simd_clamp(-max, max);
+
+                // apply in two steps, so each factor stays a normal number
+                let n1 = StdFloat::floor(n * Self::splat(0.5));
+                self * pow2(n1) * pow2(n - n1)
+            }
+
+            #[inline(always)]
+            fn frexp(self) -> (Self, Self) {
+                let mant = $scalar::MANTISSA_DIGITS as $bits - 1;
+                let bias = $scalar::MAX_EXP as $bits - 1;
+
+                // scale subnormals into the normal range first
+                let sub = self.simd_lt(Self::splat($scalar::MIN_POSITIVE));
+                let scale = ((1 as $bits) << mant) as $scalar;
+                let x = sub.select(self * Self::splat(scale), self);
+                let offset = sub.select(
+                    Self::splat((bias + mant) as $scalar),
+                    Self::splat(bias as $scalar)
+                );
+
+                let bits = x.to_bits();
+                let e = (bits >> mant).cast::<$scalar>() - offset;
+                let m = Simd::from_bits(
+                    (bits & Simd::splat(((1 as $bits) << mant) - 1)) | Simd::splat(bias << mant)
+                );
+                (m, e)
+            }
+        }
+    )* )
+}
+
+impl_portable!(f32: u32, i32; 2, 4, 8, 16);
+impl_portable!(f64: u64, i64; 2, 4, 8, 16);
+
+#[test]
+fn test_simd_mask() {
+    let a = Simd::from_array([1.0f32, -2., 3., f32::NAN, 0., 7., -0.5, 2.]);
+    let pos = a.gt(Simd::splat(0.));
+    let small = a.lt(Simd::splat(2.));
+    assert_eq!(pos, SimdMask::from_array([true, false, true, false, false, true, false, true]));
+    assert_eq!(Mask::and(pos, small), SimdMask::from_array([true, false, false, false, false, false, false, false]));
+    assert_eq!(Mask::or(pos, small), SimdMask::from_array([true, true, true, false, true, true, true, true]));
+    assert_eq!(Mask::xor(pos, small), SimdMask::from_array([false, true, true, false, true, true, true, true]));
+    assert_eq!(Mask::not(pos), SimdMask::from_array([false, true, false, true, true, false, true, false]));
+    assert!(Mask::any(pos) && !Mask::all(pos) && !Mask::none(pos));
+    assert_eq!(Mask::count_true(pos), 4);
+    assert_eq!(Mask::count_true(Real::is_nan(a)), 1);
+    assert_eq!(Mask::count_true(<SimdMask<i32, 8> as Mask>::splat(true)), 8);
+    assert!(Mask::none(<SimdMask<i32, 8> as Mask>::splat(false)));
+
+    let b = Simd::from_array([-1.0f64, 5.]);
+    assert_eq!(Mask::count_true(Real::ge(b, Simd::splat(-1.))), 2);
+}
+
+#[test]
+fn test_simd_f32() {
+    approx::check_lanes::<Simd<f32, 2>>(1e-6);
+    approx::check_lanes::<Simd<f32, 4>>(1e-6);
+    approx::check_lanes::<Simd<f32, 8>>(1e-6);
+    approx::check_lanes::<Simd<f32, 16>>(1e-6);
+}
+
+#[test]
+fn test_simd_f64() {
+    approx::check_lanes::<Simd<f64, 2>>(1e-14);
+    approx::check_lanes::<Simd<f64, 4>>(1e-14);
+    approx::check_lanes::<Simd<f64, 8>>(1e-14);
+    approx::check_lanes::<Simd<f64, 16>>(1e-14);
+}
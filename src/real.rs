@@ -1,25 +1,147 @@
 use std::ops::{Add, Sub, Mul, Div};
 use cast::Cast;
+use mask::Mask;
 use rand::{Rng};
 use std::fmt::Debug;
 use tuple::*;
 
+// The scalar constructors as const fns, so tables can be built in const contexts.
+// The Real impls for f32 and f64 use them too.
+macro_rules! const_fns {
+    ($($t:ident: $from_i32:ident, $from_i64:ident, $from_u32:ident, $ratio:ident;)*) => ($(
+        #[doc = concat!("`Real::from_i32` for ", stringify!($t), ", usable in const contexts")]
+        #[inline(always)]
+        pub const fn $from_i32(v: i32) -> $t { v as $t }
+
+        #[doc = concat!("`Real::from_i64` for ", stringify!($t), ", usable in const contexts")]
+        #[inline(always)]
+        pub const fn $from_i64(v: i64) -> $t { v as $t }
+
+        #[doc = concat!("`Real::from_u32` for ", stringify!($t), ", usable in const contexts")]
+        #[inline(always)]
+        pub const fn $from_u32(v: u32) -> $t { v as $t }
+
+        #[doc = concat!("`Real::from_ratio` for ", stringify!($t), ", usable in const contexts")]
+        #[inline(always)]
+        pub const fn $ratio(nom: i64, denom: u64) -> $t { (nom as f64 / denom as f64) as $t }
+    )*)
+}
+const_fns!(
+    f32: f32_from_i32, f32_from_i64, f32_from_u32, f32_ratio;
+    f64: f64_from_i32, f64_from_i64, f64_from_u32, f64_ratio;
+);
+
 //Float + NumCast + SampleRange + PartialOrd + Clone + Add + Debug
 pub trait Real:
     Sized + Copy + Debug
   + Mul<Output=Self> + Add<Output=Self> + Sub<Output=Self> + Div<Output=Self>
 {
     const PI: Self;
-    type Bool;
-    type Scalar;
+    /// Euler's number
+    const E: Self;
+    /// 2 pi
+    const TAU: Self;
+    /// pi / 2
+    const FRAC_PI_2: Self;
+    /// ln(2)
+    const LN_2: Self;
+    /// ln(10)
+    const LN_10: Self;
+    /// sqrt(2)
+    const SQRT_2: Self;
+    /// difference between 1 and the next larger value
+    const EPSILON: Self;
+    /// +inf
+    const INFINITY: Self;
+    /// -inf
+    const NEG_INFINITY: Self;
+    /// NaN
+    const NAN: Self;
+    /// smallest positive normal value
+    const MIN_POSITIVE: Self;
+    /// largest finite value
+    const MAX: Self;
+    /// smallest finite value
+    const MIN: Self;
+
+    /// number of scalars in Self
+    const LANES: usize;
+
+    type Bool: Mask;
+    type Scalar: Copy;
     type Iterator: Iterator<Item=Self::Scalar>;
 
     fn values(self) -> Self::Iterator;
+
+    /// load the first LANES values of s.
+    /// panics if s is shorter than LANES.
+    fn from_slice(s: &[Self::Scalar]) -> Self;
+
+    /// like from_slice, but s has to be aligned to Self
+    #[inline]
+    fn from_slice_aligned(s: &[Self::Scalar]) -> Self {
+        Self::from_slice(s)
+    }
+
+    /// load up to LANES values of s and fill the remaining lanes with pad
+    fn from_slice_padded(s: &[Self::Scalar], pad: Self::Scalar) -> Self;
+
+    /// store all lanes in the first LANES values of s.
+    /// panics if s is shorter than LANES.
+    fn write_to_slice(self, s: &mut [Self::Scalar]);
+
+    /// store as many lanes as fit into s
+    fn write_to_slice_partial(self, s: &mut [Self::Scalar]);
     
     fn int(v: i16) -> Self;
+
+    /// f converted like `as`: values beyond the range of Scalar become +-inf
     fn float(f: f64) -> Self;
+
+    /// None if f is finite but beyond the range of Scalar
+    fn try_float(f: f64) -> Option<Self>;
+
+    /// f clamped to the finite range of Scalar, NaN stays NaN
+    fn float_saturating(f: f64) -> Self;
+
+    /// s converted with `Cast`, None if it does not fit
+    #[inline]
+    fn from_scalar<S: Cast<Self::Scalar>>(s: S) -> Option<Self> {
+        s.cast().map(Self::splat)
+    }
     
+    /// nom / denom, see `from_ratio` for wider inputs
     fn frac(nom: i16, denom: u16) -> Self;
+
+    /// v rounded to the nearest Scalar
+    #[inline]
+    fn from_i32(v: i32) -> Self {
+        Self::float(v as f64)
+    }
+    /// v rounded to the nearest Scalar
+    ///
+    /// The default goes through f64, so beyond 2^53 it may round twice.
+    #[inline]
+    fn from_i64(v: i64) -> Self {
+        Self::float(v as f64)
+    }
+    /// v rounded to the nearest Scalar
+    #[inline]
+    fn from_u32(v: u32) -> Self {
+        Self::float(v as f64)
+    }
+
+    /// nom / denom, `frac` for wider inputs.
+    /// (`frac` keeps its signature, so existing implementations of `Real` still compile.)
+    ///
+    /// The quotient is computed in f64. It is correctly rounded if nom and denom are
+    /// at most 2^53 in magnitude, also for f32: f64 has more than twice its precision,
+    /// so rounding the f64 quotient to f32 gives the same result as dividing in f32.
+    /// Larger operands are rounded to f64 before the division.
+    #[inline]
+    fn from_ratio(nom: i64, denom: u64) -> Self {
+        Self::float(nom as f64 / denom as f64)
+    }
     #[inline]
     fn inv(self) -> Self {
         <Self as Real>::int(1) / self
@@ -33,17 +155,70 @@ pub trait Real:
     /// sqrt(x)
     fn sqrt(self) -> Self { unimplemented!() }
     
-    fn pow(self) -> Self { unimplemented!() }
-    
-    /* TODO: needs simd impl
+    /// x^e
+    fn powf(self, e: Self) -> Self;
+
+    /// x^n
+    #[inline]
+    fn powi(self, n: i32) -> Self {
+        let mut base = self;
+        let mut k = n.unsigned_abs();
+        let mut acc = <Self as Real>::int(1);
+        while k > 0 {
+            if k & 1 == 1 {
+                acc = acc * base;
+            }
+            base = base * base;
+            k >>= 1;
+        }
+        if n < 0 { acc.inv() } else { acc }
+    }
+
+    /// 2^x
+    fn exp2(self) -> Self;
+
+    /// log2(x)
+    fn log2(self) -> Self;
+
+    /// sin(x)
     fn sin(self) -> Self;
+
+    /// cos(x)
     fn cos(self) -> Self;
+
+    /// e^x
     fn exp(self) -> Self;
+
+    /// natural logarithm of x
     fn ln(self) -> Self;
-    */
 
     fn floor(self) -> Self;
     fn ceil(self) -> Self;
+
+    /// round to the nearest integer, ties away from zero
+    fn round(self) -> Self;
+
+    /// round towards zero
+    fn trunc(self) -> Self;
+
+    /// fractional part, with the sign of self
+    #[inline]
+    fn fract(self) -> Self {
+        self - self.trunc()
+    }
+
+    /// 1 with the sign of self (also for zeros), NaN for NaN
+    fn signum(self) -> Self;
+
+    /// |self| with the sign of sign
+    fn copysign(self, sign: Self) -> Self;
+
+    fn is_nan(self) -> Self::Bool;
+    fn is_finite(self) -> Self::Bool;
+    fn is_infinite(self) -> Self::Bool;
+
+    /// true if the sign bit is set, including -0.0
+    fn is_sign_negative(self) -> Self::Bool;
     
     /// self * b + c
     #[inline]
@@ -56,6 +231,18 @@ pub trait Real:
     
     fn splat(s: Self::Scalar) -> Self;
 
+    /// sum of all lanes
+    fn reduce_sum(self) -> Self::Scalar;
+
+    /// product of all lanes
+    fn reduce_product(self) -> Self::Scalar;
+
+    /// smallest lane
+    fn reduce_min(self) -> Self::Scalar;
+
+    /// largest lane
+    fn reduce_max(self) -> Self::Scalar;
+
     #[inline]
     fn clamp(self, min: Self, max: Self) -> Self {
         let clamped_low = min.select(self, self.lt(min));
@@ -81,9 +268,23 @@ pub trait Real:
 }
 
 macro_rules! impl_real {
-    ($($t:ident : $fma:ident),*) => ( $(
+    ($($t:ident: $from_i32:ident, $from_i64:ident, $from_u32:ident, $ratio:ident);*) => ( $(
         impl Real for $t {
             const PI: Self = ::std::$t::consts::PI;
+            const E: Self = ::std::$t::consts::E;
+            const TAU: Self = ::std::$t::consts::TAU;
+            const FRAC_PI_2: Self = ::std::$t::consts::FRAC_PI_2;
+            const LN_2: Self = ::std::$t::consts::LN_2;
+            const LN_10: Self = ::std::$t::consts::LN_10;
+            const SQRT_2: Self = ::std::$t::consts::SQRT_2;
+            const EPSILON: Self = $t::EPSILON;
+            const INFINITY: Self = $t::INFINITY;
+            const NEG_INFINITY: Self = $t::NEG_INFINITY;
+            const NAN: Self = $t::NAN;
+            const MIN_POSITIVE: Self = $t::MIN_POSITIVE;
+            const MAX: Self = $t::MAX;
+            const MIN: Self = $t::MIN;
+            const LANES: usize = 1;
             type Bool = bool;
             type Scalar = $t;
             type Iterator = ::std::iter::Once<$t>;
@@ -97,17 +298,80 @@ macro_rules! impl_real {
             fn values(self) -> Self::Iterator {
                 ::std::iter::once(self)
             }
+
+            #[inline(always)]
+            fn from_slice(s: &[Self::Scalar]) -> Self {
+                s[0]
+            }
+
+            #[inline(always)]
+            fn from_slice_padded(s: &[Self::Scalar], pad: Self::Scalar) -> Self {
+                s.first().cloned().unwrap_or(pad)
+            }
+
+            #[inline(always)]
+            fn write_to_slice(self, s: &mut [Self::Scalar]) {
+                s[0] = self;
+            }
+
+            #[inline(always)]
+            fn write_to_slice_partial(self, s: &mut [Self::Scalar]) {
+                if let Some(v) = s.first_mut() {
+                    *v = self;
+                }
+            }
+
+            #[inline(always)]
+            fn reduce_sum(self) -> Self::Scalar { self }
+
+            #[inline(always)]
+            fn reduce_product(self) -> Self::Scalar { self }
+
+            #[inline(always)]
+            fn reduce_min(self) -> Self::Scalar { self }
+
+            #[inline(always)]
+            fn reduce_max(self) -> Self::Scalar { self }
             
             #[inline(always)]
             fn int(v: i16) -> Self { v.into() }
             
             #[inline(always)]
-            fn float(f: f64) -> Self { f.cast().unwrap() }
+            fn float(f: f64) -> Self { f as $t }
+
+            #[inline]
+            fn try_float(f: f64) -> Option<Self> {
+                let v = f as $t;
+                if v.is_infinite() && f.is_finite() { None } else { Some(v) }
+            }
+
+            #[inline]
+            fn float_saturating(f: f64) -> Self {
+                if f > $t::MAX as f64 {
+                    $t::MAX
+                } else if f < $t::MIN as f64 {
+                    $t::MIN
+                } else {
+                    f as $t
+                }
+            }
 
             #[inline]
             fn frac(nom: i16, denom: u16) -> Self {
                 $t::from(nom) / $t::from(denom)
             }
+
+            #[inline(always)]
+            fn from_i32(v: i32) -> Self { $from_i32(v) }
+
+            #[inline(always)]
+            fn from_i64(v: i64) -> Self { $from_i64(v) }
+
+            #[inline(always)]
+            fn from_u32(v: u32) -> Self { $from_u32(v) }
+
+            #[inline(always)]
+            fn from_ratio(nom: i64, denom: u64) -> Self { $ratio(nom, denom) }
             #[inline]
             fn wrap(self, at: Self, span: Self) -> Self {
                 if self > at { self - span } else { self }
@@ -120,8 +384,7 @@ macro_rules! impl_real {
             #[cfg(target_feature="fma")]
             #[inline]
             fn mul_add(self, b: Self, c: Self) -> Self {
-                use std::intrinsics;
-                unsafe { intrinsics::$fma(self, b, c) }
+                $t::mul_add(self, b, c)
             }
             
             #[inline(always)]
@@ -130,19 +393,29 @@ macro_rules! impl_real {
             #[inline(always)]
             fn sqrt(self) -> Self { self.sqrt() }
 
-            /*
             #[inline(always)]
-            fn sin(self) -> Self { self.sin() }
+            fn sin(self) -> Self { $t::sin(self) }
+
+            #[inline(always)]
+            fn cos(self) -> Self { $t::cos(self) }
+
+            #[inline(always)]
+            fn exp(self) -> Self { $t::exp(self) }
+
+            #[inline(always)]
+            fn ln(self) -> Self { $t::ln(self) }
 
             #[inline(always)]
-            fn cos(self) -> Self { self.cos() }
+            fn powf(self, e: Self) -> Self { $t::powf(self, e) }
 
             #[inline(always)]
-            fn exp(self) -> Self { self.exp() }
+            fn powi(self, n: i32) -> Self { $t::powi(self, n) }
 
             #[inline(always)]
-            fn ln(self) -> Self { self.ln() }
-             */
+            fn exp2(self) -> Self { $t::exp2(self) }
+
+            #[inline(always)]
+            fn log2(self) -> Self { $t::log2(self) }
 
             #[inline(always)]
             fn floor(self) -> Self {
@@ -152,6 +425,33 @@ macro_rules! impl_real {
             fn ceil(self) -> Self {
                 $t::ceil(self)
             }
+
+            #[inline(always)]
+            fn round(self) -> Self { $t::round(self) }
+
+            #[inline(always)]
+            fn trunc(self) -> Self { $t::trunc(self) }
+
+            #[inline(always)]
+            fn fract(self) -> Self { $t::fract(self) }
+
+            #[inline(always)]
+            fn signum(self) -> Self { $t::signum(self) }
+
+            #[inline(always)]
+            fn copysign(self, sign: Self) -> Self { $t::copysign(self, sign) }
+
+            #[inline(always)]
+            fn is_nan(self) -> Self::Bool { $t::is_nan(self) }
+
+            #[inline(always)]
+            fn is_finite(self) -> Self::Bool { $t::is_finite(self) }
+
+            #[inline(always)]
+            fn is_infinite(self) -> Self::Bool { $t::is_infinite(self) }
+
+            #[inline(always)]
+            fn is_sign_negative(self) -> Self::Bool { $t::is_sign_negative(self) }
             
             #[inline(always)]
             fn lt(self, rhs: Self) -> Self::Bool { self < rhs }
@@ -176,7 +476,15 @@ macro_rules! impl_real {
     )* )
 }
 
-impl_real!(f32: fmaf32, f64: fmaf64);
+impl_real!(
+    f32: f32_from_i32, f32_from_i64, f32_from_u32, f32_ratio;
+    f64: f64_from_i32, f64_from_i64, f64_from_u32, f64_ratio
+);
+
+// folds the fields with `op`, starting from the first one
+macro_rules! fold_fields {
+    ($op:tt, $s:expr, $first:tt $(, $idx:tt)*) => ( $s.$first $($op $s.$idx)* )
+}
 
 macro_rules! tuple_init {
     ($($Tuple:ident $Arr:ident { $($T:ident . $t:ident . $idx:tt),* } )*) => ($(
@@ -184,6 +492,20 @@ macro_rules! tuple_init {
         impl<T: Real> Real for $Tuple<$(first_i!(T, $T),)*>
         {
             const PI: Self = $Tuple( $(first_e!(T::PI, $T),)* );
+            const E: Self = $Tuple( $(first_e!(T::E, $T),)* );
+            const TAU: Self = $Tuple( $(first_e!(T::TAU, $T),)* );
+            const FRAC_PI_2: Self = $Tuple( $(first_e!(T::FRAC_PI_2, $T),)* );
+            const LN_2: Self = $Tuple( $(first_e!(T::LN_2, $T),)* );
+            const LN_10: Self = $Tuple( $(first_e!(T::LN_10, $T),)* );
+            const SQRT_2: Self = $Tuple( $(first_e!(T::SQRT_2, $T),)* );
+            const EPSILON: Self = $Tuple( $(first_e!(T::EPSILON, $T),)* );
+            const INFINITY: Self = $Tuple( $(first_e!(T::INFINITY, $T),)* );
+            const NEG_INFINITY: Self = $Tuple( $(first_e!(T::NEG_INFINITY, $T),)* );
+            const NAN: Self = $Tuple( $(first_e!(T::NAN, $T),)* );
+            const MIN_POSITIVE: Self = $Tuple( $(first_e!(T::MIN_POSITIVE, $T),)* );
+            const MAX: Self = $Tuple( $(first_e!(T::MAX, $T),)* );
+            const MIN: Self = $Tuple( $(first_e!(T::MIN, $T),)* );
+            const LANES: usize = 0 $(+ first_e!(1, $idx))*;
             type Bool = $Tuple<$(first_t!(T::Bool, $T)),*>;
             type Scalar = T;
             type Iterator = IntoElements<Self>;
@@ -197,6 +519,48 @@ macro_rules! tuple_init {
                 self.into_elements()
             }
 
+            #[inline]
+            fn from_slice(s: &[Self::Scalar]) -> Self {
+                $Tuple( $(s[$idx],)* )
+            }
+            #[inline]
+            fn from_slice_padded(s: &[Self::Scalar], pad: Self::Scalar) -> Self {
+                $Tuple( $(s.get($idx).cloned().unwrap_or(pad),)* )
+            }
+            #[inline]
+            fn write_to_slice(self, s: &mut [Self::Scalar]) {
+                $( s[$idx] = self.$idx; )*
+            }
+            #[inline]
+            fn write_to_slice_partial(self, s: &mut [Self::Scalar]) {
+                $(
+                    if let Some(v) = s.get_mut($idx) {
+                        *v = self.$idx;
+                    }
+                )*
+            }
+
+            #[inline]
+            fn reduce_sum(self) -> Self::Scalar {
+                fold_fields!(+, self, $($idx),*)
+            }
+            #[inline]
+            fn reduce_product(self) -> Self::Scalar {
+                fold_fields!(*, self, $($idx),*)
+            }
+            #[inline]
+            fn reduce_min(self) -> Self::Scalar {
+                let m = self.0;
+                $( let m = T::min(m, self.$idx); )*
+                m
+            }
+            #[inline]
+            fn reduce_max(self) -> Self::Scalar {
+                let m = self.0;
+                $( let m = T::max(m, self.$idx); )*
+                m
+            }
+
             #[inline]
             fn int(v: i16) -> Self {
                 $Tuple( $(first_e!(T::int(v), $idx),)* )
@@ -206,9 +570,34 @@ macro_rules! tuple_init {
                 $Tuple( $(first_e!(T::float(f), $idx),)* )
             }
             #[inline]
+            fn try_float(f: f64) -> Option<Self> {
+                let v = T::try_float(f)?;
+                Some($Tuple( $(first_e!(v, $idx),)* ))
+            }
+            #[inline]
+            fn float_saturating(f: f64) -> Self {
+                $Tuple( $(first_e!(T::float_saturating(f), $idx),)* )
+            }
+            #[inline]
             fn frac(nom: i16, denom: u16) -> Self {
                 $Tuple( $(first_e!(T::frac(nom, denom), $idx),)* )
             }
+            #[inline]
+            fn from_i32(v: i32) -> Self {
+                $Tuple( $(first_e!(T::from_i32(v), $idx),)* )
+            }
+            #[inline]
+            fn from_i64(v: i64) -> Self {
+                $Tuple( $(first_e!(T::from_i64(v), $idx),)* )
+            }
+            #[inline]
+            fn from_u32(v: u32) -> Self {
+                $Tuple( $(first_e!(T::from_u32(v), $idx),)* )
+            }
+            #[inline]
+            fn from_ratio(nom: i64, denom: u64) -> Self {
+                $Tuple( $(first_e!(T::from_ratio(nom, denom), $idx),)* )
+            }
             
             #[inline]
             fn uniform01<R: Rng>(rng: &mut R) -> Self {
@@ -225,6 +614,46 @@ macro_rules! tuple_init {
                 $Tuple( $(T::sqrt(self.$idx)),* )
             }
 
+            #[inline]
+            fn sin(self) -> Self {
+                $Tuple( $(T::sin(self.$idx)),* )
+            }
+
+            #[inline]
+            fn cos(self) -> Self {
+                $Tuple( $(T::cos(self.$idx)),* )
+            }
+
+            #[inline]
+            fn exp(self) -> Self {
+                $Tuple( $(T::exp(self.$idx)),* )
+            }
+
+            #[inline]
+            fn ln(self) -> Self {
+                $Tuple( $(T::ln(self.$idx)),* )
+            }
+
+            #[inline]
+            fn powf(self, e: Self) -> Self {
+                $Tuple( $(T::powf(self.$idx, e.$idx)),* )
+            }
+
+            #[inline]
+            fn powi(self, n: i32) -> Self {
+                $Tuple( $(T::powi(self.$idx, n)),* )
+            }
+
+            #[inline]
+            fn exp2(self) -> Self {
+                $Tuple( $(T::exp2(self.$idx)),* )
+            }
+
+            #[inline]
+            fn log2(self) -> Self {
+                $Tuple( $(T::log2(self.$idx)),* )
+            }
+
             #[inline]
             fn wrap(self, at: Self, span: Self) -> Self {
                 $Tuple( $(T::wrap(self.$idx, at.$idx, span.$idx),)* )
@@ -245,6 +674,51 @@ macro_rules! tuple_init {
                 $Tuple( $(T::ceil(self.$idx),)* )
             }
 
+            #[inline]
+            fn round(self) -> Self {
+                $Tuple( $(T::round(self.$idx),)* )
+            }
+
+            #[inline]
+            fn trunc(self) -> Self {
+                $Tuple( $(T::trunc(self.$idx),)* )
+            }
+
+            #[inline]
+            fn fract(self) -> Self {
+                $Tuple( $(T::fract(self.$idx),)* )
+            }
+
+            #[inline]
+            fn signum(self) -> Self {
+                $Tuple( $(T::signum(self.$idx),)* )
+            }
+
+            #[inline]
+            fn copysign(self, sign: Self) -> Self {
+                $Tuple( $(T::copysign(self.$idx, sign.$idx),)* )
+            }
+
+            #[inline]
+            fn is_nan(self) -> Self::Bool {
+                $Tuple( $(T::is_nan(self.$idx),)* )
+            }
+
+            #[inline]
+            fn is_finite(self) -> Self::Bool {
+                $Tuple( $(T::is_finite(self.$idx),)* )
+            }
+
+            #[inline]
+            fn is_infinite(self) -> Self::Bool {
+                $Tuple( $(T::is_infinite(self.$idx),)* )
+            }
+
+            #[inline]
+            fn is_sign_negative(self) -> Self::Bool {
+                $Tuple( $(T::is_sign_negative(self.$idx),)* )
+            }
+
             #[inline]
             fn lt(self, rhs: Self) -> Self::Bool {
                 $Tuple( $(T::lt(self.$idx, rhs.$idx),)* )
@@ -274,3 +748,75 @@ macro_rules! tuple_init {
     )*)
 }
 impl_tuple!(tuple_init);
+
+#[test]
+fn test_reduce_tuple() {
+    let t = T4(3.0f32, -1.0, 4.0, 2.0);
+    assert_eq!(t.reduce_sum(), 8.0);
+    assert_eq!(t.reduce_product(), -24.0);
+    assert_eq!(t.reduce_min(), -1.0);
+    assert_eq!(t.reduce_max(), 4.0);
+
+    // the sign of zero survives
+    assert!(T2(-0.0f64, -0.0).reduce_sum().is_sign_negative());
+    assert!(T2(-0.0f64, 0.0).reduce_product().is_sign_negative());
+}
+
+#[test]
+fn test_slice_tuple() {
+    let data = [1.0f64, 2.0, 3.0];
+    let t = T4::<f64, f64, f64, f64>::from_slice_padded(&data, 0.0);
+    assert_eq!(t.reduce_sum(), 6.0);
+
+    let mut out = [0.0f64; 2];
+    t.write_to_slice_partial(&mut out);
+    assert_eq!(out, [1.0, 2.0]);
+}
+
+#[test]
+fn test_classify_tuple() {
+    let t = T4(-0.0f64, f64::NAN, f64::NEG_INFINITY, -2.5);
+    assert_eq!(t.is_nan(), T4(false, true, false, false));
+    assert_eq!(t.is_finite(), T4(true, false, false, true));
+    assert_eq!(t.is_infinite(), T4(false, false, true, false));
+    assert_eq!(t.is_sign_negative(), T4(true, false, true, true));
+    assert_eq!(t.round().3, -3.0);
+    assert_eq!(t.trunc().3, -2.0);
+    assert_eq!(t.fract().3, -0.5);
+    assert_eq!(t.signum().0, -1.0);
+    assert_eq!(T2(2.0f32, 3.0).copysign(T2(-0.0, 1.0)), T2(-2.0, 3.0));
+}
+
+#[test]
+fn test_consts_tuple() {
+    let t = T2::<f32, f32>::E;
+    assert_eq!(t, T2(::std::f32::consts::E, ::std::f32::consts::E));
+    assert!(T2::<f64, f64>::NAN.is_nan().0);
+    assert_eq!(T2::<f64, f64>::MIN.1, f64::MIN);
+}
+
+#[test]
+fn test_float_constructors() {
+    assert_eq!(<f32 as Real>::float(1e300), f32::INFINITY);
+    assert_eq!(<f32 as Real>::try_float(1e300), None);
+    assert_eq!(<f32 as Real>::try_float(f64::NEG_INFINITY), Some(f32::NEG_INFINITY));
+    assert_eq!(<f32 as Real>::float_saturating(-1e300), f32::MIN);
+    assert!(<f32 as Real>::float_saturating(f64::NAN).is_nan());
+    assert_eq!(<f64 as Real>::float_saturating(f64::INFINITY), f64::MAX);
+    assert_eq!(T2::<f32, f32>::try_float(0.5), Some(T2(0.5, 0.5)));
+    assert_eq!(T2::<f32, f32>::from_scalar(300u16), Some(T2(300., 300.)));
+    assert_eq!(<f32 as Real>::from_scalar(1e300f64), None);
+}
+
+#[test]
+fn test_wide_constructors() {
+    const RATES: [f32; 2] = [f32_from_u32(44100), f32_ratio(1, 48000)];
+    assert_eq!(RATES, [44100.0, 1.0 / 48000.0]);
+    assert_eq!(<f64 as Real>::from_i64(-(1 << 40)), -1099511627776.0);
+    assert_eq!(<f64 as Real>::from_ratio(-1, 3), -1.0 / 3.0);
+    // correctly rounded f32 quotients
+    for &(n, d) in &[(1, 3), (-7, 10), (16777215, 16777213), (44100, 48000)] {
+        assert_eq!(<f32 as Real>::from_ratio(n, d), n as f32 / d as f32);
+    }
+    assert_eq!(T2::<f32, f32>::from_i32(-44100), T2(-44100.0, -44100.0));
+}
@@ -2,21 +2,115 @@ use std::mem::transmute;
 use rand::Rng;
 use tuple::*;
 use real::Real;
+use mask::Mask;
+use approx::{self, Exponent};
+use std::arch::x86_64::*;
+use std::ops::RangeInclusive;
+use cast::{Cast, CastSlice, Round, Rounding};
 
 // highly unsafe macro
+// calls the intrinsic $name, transmuting the arguments and result between
+// the packed_simd types and the std::arch types of the same size.
 macro_rules! call {
-    ($pre:ident, $name:ident, $post:ident ( $($arg:expr),* ) ) => (
+    ($name:ident ( $($arg:expr),* ) ) => (
         unsafe {
-            use std::arch::x86_64::*;
-            transmute(concat_idents!($pre, $name, $post)( $( transmute($arg) ),* ))
+            transmute($name( $( transmute($arg) ),* ))
         }
     )
 }
 
+// fold the upper half of the lanes onto the lower half until one lane is left
+macro_rules! reduce {
+    (T2, $v:expr, $f:expr) => ({
+        let v = $v;
+        let v = $f(v, shuffle!(v, [1, 0]));
+        v.extract(0)
+    });
+    (T4, $v:expr, $f:expr) => ({
+        let v = $v;
+        let v = $f(v, shuffle!(v, [2, 3, 0, 1]));
+        let v = $f(v, shuffle!(v, [1, 0, 3, 2]));
+        v.extract(0)
+    });
+    (T8, $v:expr, $f:expr) => ({
+        let v = $v;
+        let v = $f(v, shuffle!(v, [4, 5, 6, 7, 0, 1, 2, 3]));
+        let v = $f(v, shuffle!(v, [2, 3, 0, 1, 6, 7, 4, 5]));
+        let v = $f(v, shuffle!(v, [1, 0, 3, 2, 5, 4, 7, 6]));
+        v.extract(0)
+    });
+}
+
+/// The instructions behind the `Real` methods of the vector types.
+///
+/// `Real` only uses them if their target feature is enabled at compile time,
+/// these methods always do, so the caller has to make sure the cpu supports them.
+pub(crate) trait Native: Exponent {
+    unsafe fn sqrt(self) -> Self;
+    unsafe fn floor(self) -> Self;
+    unsafe fn ceil(self) -> Self;
+    unsafe fn min(self, other: Self) -> Self;
+    unsafe fn max(self, other: Self) -> Self;
+    unsafe fn select(self, other: Self, cond: Self::Bool) -> Self;
+    /// needs FMA
+    unsafe fn mul_add(self, b: Self, c: Self) -> Self;
+
+    /// round to the nearest integer, ties to even, without SSE4.1
+    fn round_even(self) -> Self;
+}
+
+// $base is the target feature needed by sqrt, min and max,
+// $round the one needed by floor, ceil and blendv.
 macro_rules! impl_simd {
-    ($($size:tt, $simd:ident: $scalar:ident, $bool:ty, $pre:ident ~ $post:ident, $Tuple:ident($($idx:tt)*));*) => ( $(
+    ($($size:tt, $simd:ident: $scalar:ident, $bool:ty, $int:ident: $iscalar:ident,
+        $base:tt: [$sqrt:ident, $min:ident, $max:ident], $round:tt: [$floor:ident, $ceil:ident, $blendv:ident], $fmadd:ident,
+        $Tuple:ident($($idx:tt)*));*) => ( $(
+        impl Native for $simd {
+            #[inline(always)]
+            unsafe fn sqrt(self) -> Self { call!($sqrt(self)) }
+            #[inline(always)]
+            unsafe fn floor(self) -> Self { call!($floor(self)) }
+            #[inline(always)]
+            unsafe fn ceil(self) -> Self { call!($ceil(self)) }
+            #[inline(always)]
+            unsafe fn min(self, other: Self) -> Self { call!($min(self, other)) }
+            #[inline(always)]
+            unsafe fn max(self, other: Self) -> Self { call!($max(self, other)) }
+            #[inline(always)]
+            unsafe fn select(self, other: Self, cond: Self::Bool) -> Self {
+                // blendv takes the second operand where the mask is set
+                call!($blendv(other, self, cond))
+            }
+            #[inline(always)]
+            unsafe fn mul_add(self, b: Self, c: Self) -> Self { call!($fmadd(self, b, c)) }
+
+            #[inline(always)]
+            fn round_even(self) -> Self {
+                // adding and subtracting 2^(mantissa bits) leaves the rounding to the fpu,
+                // larger values are integral already
+                let big = $simd::splat((1u64 << ($scalar::MANTISSA_DIGITS - 1)) as $scalar);
+                let a = Real::abs(self);
+                let r = Real::select((a + big) - big, a, a.lt(big));
+                Real::copysign(r, self)
+            }
+        }
+
         impl Real for $simd {
             const PI: Self = $simd::splat(::std::$scalar::consts::PI);
+            const E: Self = $simd::splat(::std::$scalar::consts::E);
+            const TAU: Self = $simd::splat(::std::$scalar::consts::TAU);
+            const FRAC_PI_2: Self = $simd::splat(::std::$scalar::consts::FRAC_PI_2);
+            const LN_2: Self = $simd::splat(::std::$scalar::consts::LN_2);
+            const LN_10: Self = $simd::splat(::std::$scalar::consts::LN_10);
+            const SQRT_2: Self = $simd::splat(::std::$scalar::consts::SQRT_2);
+            const EPSILON: Self = $simd::splat($scalar::EPSILON);
+            const INFINITY: Self = $simd::splat($scalar::INFINITY);
+            const NEG_INFINITY: Self = $simd::splat($scalar::NEG_INFINITY);
+            const NAN: Self = $simd::splat($scalar::NAN);
+            const MIN_POSITIVE: Self = $simd::splat($scalar::MIN_POSITIVE);
+            const MAX: Self = $simd::splat($scalar::MAX);
+            const MIN: Self = $simd::splat($scalar::MIN);
+            const LANES: usize = 0 $(+ first_e!(1, $idx))*;
             type Bool = $bool;
             type Scalar = $scalar;
             type Iterator = IntoElements<$Tuple<$(first_t!($scalar, $idx)),*>>;
@@ -32,21 +126,84 @@ macro_rules! impl_simd {
                 struct Arr([$scalar; 0$(+ first_e!(1, $idx))*]);
             
                 let mut arr = Arr([$(first_e!(0., $idx)),*]);
-                self.store(&mut arr.0, 0);
+                self.write_to_slice_aligned(&mut arr.0);
                 $Tuple::from(arr.0).into_elements()
             }
 
+            #[inline(always)]
+            fn from_slice(s: &[Self::Scalar]) -> Self {
+                $simd::from_slice_unaligned(s)
+            }
+
+            #[inline(always)]
+            fn from_slice_aligned(s: &[Self::Scalar]) -> Self {
+                $simd::from_slice_aligned(s)
+            }
+
+            #[inline(always)]
+            fn from_slice_padded(s: &[Self::Scalar], pad: Self::Scalar) -> Self {
+                let mut arr = [pad; <Self as Real>::LANES];
+                let n = s.len().min(<Self as Real>::LANES);
+                arr[.. n].copy_from_slice(&s[.. n]);
+                $simd::from_slice_unaligned(&arr)
+            }
+
+            #[inline(always)]
+            fn write_to_slice(self, s: &mut [Self::Scalar]) {
+                self.write_to_slice_unaligned(s)
+            }
+
+            #[inline(always)]
+            fn write_to_slice_partial(self, s: &mut [Self::Scalar]) {
+                let mut arr = [0.; <Self as Real>::LANES];
+                self.write_to_slice_unaligned(&mut arr);
+                let n = s.len().min(<Self as Real>::LANES);
+                s[.. n].copy_from_slice(&arr[.. n]);
+            }
+
+            #[inline(always)]
+            fn reduce_sum(self) -> Self::Scalar {
+                reduce!($Tuple, self, |a: Self, b: Self| a + b)
+            }
+            #[inline(always)]
+            fn reduce_product(self) -> Self::Scalar {
+                reduce!($Tuple, self, |a: Self, b: Self| a * b)
+            }
+            #[inline(always)]
+            fn reduce_min(self) -> Self::Scalar {
+                reduce!($Tuple, self, |a: Self, b: Self| Real::min(a, b))
+            }
+            #[inline(always)]
+            fn reduce_max(self) -> Self::Scalar {
+                reduce!($Tuple, self, |a: Self, b: Self| Real::max(a, b))
+            }
+
             #[inline(always)]
             fn int(v: i16) -> Self { Self::splat($scalar::from(v)) }
             #[inline]
             fn float(f: f64) -> Self {
-                let f = f as $scalar;
-                Self::splat($scalar::from(f))
+                Self::splat(f as $scalar)
+            }
+            #[inline]
+            fn try_float(f: f64) -> Option<Self> {
+                <$scalar as Real>::try_float(f).map(Self::splat)
+            }
+            #[inline]
+            fn float_saturating(f: f64) -> Self {
+                Self::splat(<$scalar as Real>::float_saturating(f))
             }
             #[inline]
             fn frac(nom: i16, denom: u16) -> Self {
                 Self::splat($scalar::from(nom) / $scalar::from(denom))
             }
+            #[inline]
+            fn from_i32(v: i32) -> Self { Self::splat(<$scalar as Real>::from_i32(v)) }
+            #[inline]
+            fn from_i64(v: i64) -> Self { Self::splat(<$scalar as Real>::from_i64(v)) }
+            #[inline]
+            fn from_u32(v: u32) -> Self { Self::splat(<$scalar as Real>::from_u32(v)) }
+            #[inline]
+            fn from_ratio(nom: i64, denom: u64) -> Self { Self::splat(<$scalar as Real>::from_ratio(nom, denom)) }
 
             #[inline(always)]
             fn wrap(self, at: Self, span: Self) -> Self {
@@ -56,51 +213,125 @@ macro_rules! impl_simd {
             fn uniform01<R: Rng>(rng: &mut R) -> Self {
                 $simd::new($(first_e!(rng.gen(), $idx)),*)
             }
-/*
+
             #[cfg(target_feature="fma")]
-            #[inline]
+            #[inline(always)]
             fn mul_add(self, b: Self, c: Self) -> Self {
-                call!($pre, Fma::mul_add(self, b, c)
+                unsafe { Native::mul_add(self, b, c) }
             }
-*/
+
             #[inline(always)]
             fn abs(self) -> Self {
-                Real::select(-self, self, self.le(Self::splat(0.0)))
+                // clear the sign bit, so -0.0 and NaN are handled too
+                $simd::from_bits($int::from_bits(self) & $int::splat($iscalar::MAX))
             }
             #[inline(always)]
             fn sqrt(self) -> Self {
-                call!($pre, sqrt, $post (self))
+                if cfg!(target_feature=$base) {
+                    unsafe { Native::sqrt(self) }
+                } else {
+                    $simd::sqrt(self)
+                }
             }
 
-            /*
+
             #[inline(always)]
-            fn sin(self) -> Self { self.sin() }
+            fn sin(self) -> Self { approx::sin(self) }
 
             #[inline(always)]
-            fn cos(self) -> Self { self.cos() }
+            fn cos(self) -> Self { approx::cos(self) }
 
             #[inline(always)]
-            fn exp(self) -> Self { self.exp() }
+            fn exp(self) -> Self { approx::exp(self) }
 
             #[inline(always)]
-            fn ln(self) -> Self { self.ln() }
-            */
+            fn ln(self) -> Self { approx::ln(self) }
+
+            #[inline(always)]
+            fn powf(self, e: Self) -> Self { approx::powf(self, e) }
+
+            #[inline(always)]
+            fn exp2(self) -> Self { approx::exp2(self) }
+
+            #[inline(always)]
+            fn log2(self) -> Self { approx::log2(self) }
+
             #[inline(always)]
             fn floor(self) -> Self {
-                call!($pre, floor, $post (self))
+                if cfg!(target_feature=$round) {
+                    unsafe { Native::floor(self) }
+                } else {
+                    let r = self.round_even();
+                    Real::select(r - Self::splat(1.), r, r.gt(self))
+                }
             }
             #[inline(always)]
             fn ceil(self) -> Self {
-                call!($pre, ceil, $post (self))
+                if cfg!(target_feature=$round) {
+                    unsafe { Native::ceil(self) }
+                } else {
+                    let r = self.round_even();
+                    Real::select(r + Self::splat(1.), r, r.lt(self))
+                }
+            }
+
+            #[inline(always)]
+            fn round(self) -> Self {
+                let t = Real::trunc(self);
+                let up = Real::abs(self - t).ge(Self::splat(0.5));
+                t + Real::copysign(Real::select(Self::splat(1.), Self::splat(0.), up), self)
+            }
+
+            #[inline(always)]
+            fn trunc(self) -> Self {
+                Real::copysign(Real::floor(Real::abs(self)), self)
+            }
+
+            #[inline(always)]
+            fn signum(self) -> Self {
+                Real::select(self, Real::copysign(Self::splat(1.), self), Real::is_nan(self))
+            }
+
+            #[inline(always)]
+            fn copysign(self, sign: Self) -> Self {
+                let s = $int::splat($iscalar::MIN);
+                $simd::from_bits(($int::from_bits(self) & !s) | ($int::from_bits(sign) & s))
+            }
+
+            #[inline(always)]
+            fn is_nan(self) -> Self::Bool { self.ne(self) }
+
+            #[inline(always)]
+            fn is_finite(self) -> Self::Bool {
+                Real::abs(self).lt(Self::splat($scalar::INFINITY))
+            }
+
+            #[inline(always)]
+            fn is_infinite(self) -> Self::Bool {
+                Real::abs(self).eq(Self::splat($scalar::INFINITY))
+            }
+
+            #[inline(always)]
+            fn is_sign_negative(self) -> Self::Bool {
+                $int::from_bits(self).lt($int::splat(0))
             }
             
             #[inline(always)]
             fn min(self, other: Self) -> Self {
-                call!($pre, min, $post (self, other))
+                if cfg!(target_feature=$base) {
+                    unsafe { Native::min(self, other) }
+                } else {
+                    // other if either is NaN, like minps
+                    Real::select(self, other, self.lt(other))
+                }
             }
             #[inline(always)]
             fn max(self, other: Self) -> Self {
-                call!($pre, max, $post (self, other))
+                if cfg!(target_feature=$base) {
+                    unsafe { Native::max(self, other) }
+                } else {
+                    Real::select(self, other, self.gt(other))
+                }
             }
             #[inline(always)]
             fn lt(self, rhs: Self) -> Self::Bool { $simd::lt(self, rhs) }
@@ -119,24 +350,564 @@ macro_rules! impl_simd {
 
             #[inline(always)]
             fn select(self, other: Self, cond: Self::Bool) -> Self {
-                call!($pre, blendv, $post (self, other, cond))
+                if cfg!(target_feature=$round) {
+                    unsafe { Native::select(self, other, cond) }
+                } else {
+                    cond.select(self, other)
+                }
+            }
+        }
+
+        impl Mask for $bool {
+            #[inline(always)]
+            fn splat(b: bool) -> Self { <$bool>::splat(b) }
+
+            #[inline(always)]
+            fn and(self, rhs: Self) -> Self { self & rhs }
+
+            #[inline(always)]
+            fn or(self, rhs: Self) -> Self { self | rhs }
+
+            #[inline(always)]
+            fn xor(self, rhs: Self) -> Self { self ^ rhs }
+
+            #[inline(always)]
+            fn not(self) -> Self { !self }
+
+            #[inline(always)]
+            fn any(self) -> bool { <$bool>::any(self) }
+
+            #[inline(always)]
+            fn all(self) -> bool { <$bool>::all(self) }
+
+            #[inline(always)]
+            fn none(self) -> bool { <$bool>::none(self) }
+
+            #[inline(always)]
+            fn count_true(self) -> usize {
+                self.select($int::splat(1), $int::splat(0)).wrapping_sum() as usize
+            }
+        }
+
+        impl Exponent for $simd {
+            const LDEXP_MAX: i16 = 2 * ($scalar::MAX_EXP as i16 - 2);
+
+            #[inline(always)]
+            fn ldexp(self, n: Self) -> Self {
+                // n has to be integral and within the normal exponent range
+                #[inline(always)]
+                fn pow2(n: $simd) -> $simd {
+                    let bias = $scalar::MAX_EXP as $iscalar - 1;
+                    let bits = ($int::from_cast(n) + $int::splat(bias)) << ($scalar::MANTISSA_DIGITS - 1);
+                    $simd::from_bits(bits)
+                }
+                let max = Self::splat(Self::LDEXP_MAX as $scalar);
+                // NaN lanes would make the integer conversion undefined
+                let n = Real::select(n, Self::splat(0.), n.eq(n));
+                let n = Real::clamp(n, -max, max);
+                
+                // apply in two steps, so each factor stays a normal number
+                let n1 = Real::floor(n * Self::splat(0.5));
+                self * pow2(n1) * pow2(n - n1)
+            }
+
+            #[inline(always)]
+            fn frexp(self) -> (Self, Self) {
+                let mant = $scalar::MANTISSA_DIGITS - 1;
+                let bias = $scalar::MAX_EXP as $iscalar - 1;
+                
+                // scale subnormals into the normal range first
+                let sub = self.lt(Self::splat($scalar::MIN_POSITIVE));
+                let scale = ((1 as $iscalar) << mant) as $scalar;
+                let x = Real::select(self * Self::splat(scale), self, sub);
+                let offset = Real::select(
+                    Self::splat((bias + mant as $iscalar) as $scalar),
+                    Self::splat(bias as $scalar),
+                    sub
+                );
+
+                let bits = $int::from_bits(x);
+                let e = $simd::from_cast(bits >> mant) - offset;
+                let m = $simd::from_bits(
+                    (bits & $int::splat(((1 as $iscalar) << mant) - 1)) | $int::splat(bias << mant)
+                );
+                (m, e)
             }
         }
     )* )
 }
 
 use simd_::*;
-impl_simd!(16, f32x4: f32, bool32fx4, _mm_ ~ _ps, T4(0 1 2 3));
+// Without the target features enabled at compile time, the methods fall back to
+// portable code, so they are safe on every x86_64 cpu. `Dispatched` uses the
+// instructions after checking the cpu at runtime.
+impl_simd!(
+    16, f32x4: f32, m32x4, i32x4: i32,
+        "sse2": [_mm_sqrt_ps, _mm_min_ps, _mm_max_ps], "sse4.1": [_mm_floor_ps, _mm_ceil_ps, _mm_blendv_ps], _mm_fmadd_ps,
+        T4(0 1 2 3);
+    16, f64x2: f64, m64x2, i64x2: i64,
+        "sse2": [_mm_sqrt_pd, _mm_min_pd, _mm_max_pd], "sse4.1": [_mm_floor_pd, _mm_ceil_pd, _mm_blendv_pd], _mm_fmadd_pd,
+        T2(0 1);
+    32, f32x8: f32, m32x8, i32x8: i32,
+        "avx": [_mm256_sqrt_ps, _mm256_min_ps, _mm256_max_ps], "avx": [_mm256_floor_ps, _mm256_ceil_ps, _mm256_blendv_ps], _mm256_fmadd_ps,
+        T8(0 1 2 3 4 5 6 7);
+    32, f64x4: f64, m64x4, i64x4: i64,
+        "avx": [_mm256_sqrt_pd, _mm256_min_pd, _mm256_max_pd], "avx": [_mm256_floor_pd, _mm256_ceil_pd, _mm256_blendv_pd], _mm256_fmadd_pd,
+        T4(0 1 2 3)
+);
 
-#[cfg(target_feature = "sse2")]
-use simd_::x86::sse2::*;
-#[cfg(target_feature = "sse2")]
-impl_simd!(16, f64x2: f64, bool64fx2, _mm_ ~ _pd, T2(0 1));
+/// A vector for the runtime dispatch: the methods use the instructions of `V`
+/// directly (see `Native`), and FMA for `mul_add` if `FMA` is set.
+///
+/// It must only be used after checking the cpu, so it is private and only
+/// created inside the `#[target_feature]` functions of `dispatch`.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct Dispatched<V, const FMA: bool>(V);
 
-#[cfg(target_feature = "avx")]
-use simd_::x86::avx::*;
-#[cfg(target_feature = "avx")]
-impl_simd!(
-    32, f32x8: f32, bool32fx8, _mm256_ ~ _ps, T8(0 1 2 3 4 5 6 7);
-    32, f64x4: f64, bool64fx4, _mm256_ ~ _pd, T4(0 1 2 3)
+macro_rules! impl_dispatched_op {
+    ($($Op:ident, $op:ident;)*) => ($(
+        impl<V: Native, const FMA: bool> ::std::ops::$Op for Dispatched<V, FMA> {
+            type Output = Self;
+            #[inline(always)]
+            fn $op(self, rhs: Self) -> Self {
+                Dispatched(::std::ops::$Op::$op(self.0, rhs.0))
+            }
+        }
+    )*)
+}
+impl_dispatched_op!(Add, add; Sub, sub; Mul, mul; Div, div;);
+
+// the methods without special instructions, forwarded to V
+macro_rules! forward {
+    (Self: $($f:ident($($arg:ident),*);)*) => ($(
+        #[inline(always)]
+        fn $f(self $(, $arg: Self)*) -> Self { Dispatched(V::$f(self.0 $(, $arg.0)*)) }
+    )*);
+    ($r:ty: $($f:ident($($arg:ident),*);)*) => ($(
+        #[inline(always)]
+        fn $f(self $(, $arg: Self)*) -> $r { V::$f(self.0 $(, $arg.0)*) }
+    )*);
+}
+
+impl<V: Native, const FMA: bool> Real for Dispatched<V, FMA> {
+    const PI: Self = Dispatched(V::PI);
+    const E: Self = Dispatched(V::E);
+    const TAU: Self = Dispatched(V::TAU);
+    const FRAC_PI_2: Self = Dispatched(V::FRAC_PI_2);
+    const LN_2: Self = Dispatched(V::LN_2);
+    const LN_10: Self = Dispatched(V::LN_10);
+    const SQRT_2: Self = Dispatched(V::SQRT_2);
+    const EPSILON: Self = Dispatched(V::EPSILON);
+    const INFINITY: Self = Dispatched(V::INFINITY);
+    const NEG_INFINITY: Self = Dispatched(V::NEG_INFINITY);
+    const NAN: Self = Dispatched(V::NAN);
+    const MIN_POSITIVE: Self = Dispatched(V::MIN_POSITIVE);
+    const MAX: Self = Dispatched(V::MAX);
+    const MIN: Self = Dispatched(V::MIN);
+    const LANES: usize = V::LANES;
+    type Bool = V::Bool;
+    type Scalar = V::Scalar;
+    type Iterator = V::Iterator;
+
+    #[inline(always)]
+    fn splat(s: Self::Scalar) -> Self { Dispatched(V::splat(s)) }
+    #[inline(always)]
+    fn from_slice(s: &[Self::Scalar]) -> Self { Dispatched(V::from_slice(s)) }
+    #[inline(always)]
+    fn from_slice_aligned(s: &[Self::Scalar]) -> Self { Dispatched(V::from_slice_aligned(s)) }
+    #[inline(always)]
+    fn from_slice_padded(s: &[Self::Scalar], pad: Self::Scalar) -> Self { Dispatched(V::from_slice_padded(s, pad)) }
+    #[inline(always)]
+    fn write_to_slice(self, s: &mut [Self::Scalar]) { self.0.write_to_slice(s) }
+    #[inline(always)]
+    fn write_to_slice_partial(self, s: &mut [Self::Scalar]) { self.0.write_to_slice_partial(s) }
+
+    #[inline(always)]
+    fn int(v: i16) -> Self { Dispatched(V::int(v)) }
+    #[inline(always)]
+    fn float(f: f64) -> Self { Dispatched(V::float(f)) }
+    #[inline(always)]
+    fn try_float(f: f64) -> Option<Self> { V::try_float(f).map(Dispatched) }
+    #[inline(always)]
+    fn float_saturating(f: f64) -> Self { Dispatched(V::float_saturating(f)) }
+    #[inline(always)]
+    fn frac(nom: i16, denom: u16) -> Self { Dispatched(V::frac(nom, denom)) }
+    #[inline(always)]
+    fn from_i32(v: i32) -> Self { Dispatched(V::from_i32(v)) }
+    #[inline(always)]
+    fn from_i64(v: i64) -> Self { Dispatched(V::from_i64(v)) }
+    #[inline(always)]
+    fn from_u32(v: u32) -> Self { Dispatched(V::from_u32(v)) }
+    #[inline(always)]
+    fn from_ratio(nom: i64, denom: u64) -> Self { Dispatched(V::from_ratio(nom, denom)) }
+    #[inline(always)]
+    fn uniform01<R: Rng>(rng: &mut R) -> Self { Dispatched(V::uniform01(rng)) }
+
+    forward!(V::Iterator: values(););
+    forward!(V::Scalar: reduce_sum(); reduce_product(); reduce_min(); reduce_max(););
+    forward!(Self: abs(); signum(); copysign(sign););
+    forward!(V::Bool:
+        is_nan(); is_finite(); is_infinite(); is_sign_negative();
+        lt(rhs); le(rhs); gt(rhs); ge(rhs); eq(rhs);
+    );
+
+    // the instructions of the level, the cpu was checked by `dispatch`
+    #[inline(always)]
+    fn sqrt(self) -> Self { Dispatched(unsafe { Native::sqrt(self.0) }) }
+    #[inline(always)]
+    fn floor(self) -> Self { Dispatched(unsafe { Native::floor(self.0) }) }
+    #[inline(always)]
+    fn ceil(self) -> Self { Dispatched(unsafe { Native::ceil(self.0) }) }
+    #[inline(always)]
+    fn min(self, other: Self) -> Self { Dispatched(unsafe { Native::min(self.0, other.0) }) }
+    #[inline(always)]
+    fn max(self, other: Self) -> Self { Dispatched(unsafe { Native::max(self.0, other.0) }) }
+    #[inline(always)]
+    fn select(self, other: Self, cond: Self::Bool) -> Self {
+        Dispatched(unsafe { Native::select(self.0, other.0, cond) })
+    }
+    #[inline(always)]
+    fn mul_add(self, b: Self, c: Self) -> Self {
+        if FMA {
+            Dispatched(unsafe { Native::mul_add(self.0, b.0, c.0) })
+        } else {
+            self * b + c
+        }
+    }
+
+    #[inline(always)]
+    fn round(self) -> Self {
+        let t = self.trunc();
+        let up = (self - t).abs().ge(Self::frac(1, 2));
+        t + Self::int(1).select(Self::int(0), up).copysign(self)
+    }
+    #[inline(always)]
+    fn trunc(self) -> Self {
+        self.abs().floor().copysign(self)
+    }
+    #[inline(always)]
+    fn wrap(self, at: Self, span: Self) -> Self {
+        (self - span).select(self, self.gt(at))
+    }
+
+    #[inline(always)]
+    fn sin(self) -> Self { approx::sin(self) }
+    #[inline(always)]
+    fn cos(self) -> Self { approx::cos(self) }
+    #[inline(always)]
+    fn exp(self) -> Self { approx::exp(self) }
+    #[inline(always)]
+    fn ln(self) -> Self { approx::ln(self) }
+    #[inline(always)]
+    fn powf(self, e: Self) -> Self { approx::powf(self, e) }
+    #[inline(always)]
+    fn exp2(self) -> Self { approx::exp2(self) }
+    #[inline(always)]
+    fn log2(self) -> Self { approx::log2(self) }
+}
+
+impl<V: Native, const FMA: bool> Exponent for Dispatched<V, FMA> {
+    const LDEXP_MAX: i16 = V::LDEXP_MAX;
+
+    #[inline(always)]
+    fn ldexp(self, n: Self) -> Self { Dispatched(self.0.ldexp(n.0)) }
+
+    #[inline(always)]
+    fn frexp(self) -> (Self, Self) {
+        let (m, e) = self.0.frexp();
+        (Dispatched(m), Dispatched(e))
+    }
+}
+
+
+// Lane-wise casts between float and integer vectors with the same number of lanes.
+// Float to integer truncates like `as`; a lane is in range if its truncated value is.
+macro_rules! impl_cast_simd {
+    ($($float:ident: $fscalar:ident => [$($int:ident: $iscalar:ident),*];)*) => ( $( $(
+        impl Cast<$int> for $float {
+            #[inline]
+            fn cast(self) -> Option<$int> {
+                let (lo, hi) = bounds!($fscalar, $iscalar);
+                let t = Real::trunc(self);
+                if (t.ge($float::splat(lo)) & t.le($float::splat(hi))).all() {
+                    Some($int::from_cast(t))
+                } else {
+                    None
+                }
+            }
+            #[inline]
+            fn cast_clipped(self, r: RangeInclusive<$int>) -> Option<$int> {
+                let (start, end) = r.into_inner();
+                let v: $int = self.cast()?;
+                if (v.ge(start) & v.le(end)).all() {
+                    Some(v)
+                } else {
+                    None
+                }
+            }
+            #[inline]
+            fn cast_clamped(self, r: RangeInclusive<$int>) -> $int {
+                let (start, end) = r.into_inner();
+                Cast::<$int>::cast_clamping(self).max(start).min(end)
+            }
+            #[inline]
+            fn cast_clamping(self) -> $int {
+                let (lo, hi) = bounds!($fscalar, $iscalar);
+                let t = Real::trunc(self);
+                // NaN turns into 0, like `as` does
+                let t = Real::select(t, $float::splat(0.), t.eq(t));
+                // hi may be below the integer maximum, so lanes above it are fixed up afterwards
+                let over = Real::select($float::splat(1.), $float::splat(0.), t.gt($float::splat(hi)));
+                let t = Real::min(Real::max(t, $float::splat(lo)), $float::splat(hi));
+                $int::from_cast(t) + $int::from_cast(over) * $int::splat($iscalar::MAX - hi as $iscalar)
+            }
+        }
+        impl Cast<$float> for $int {
+            #[inline]
+            fn cast(self) -> Option<$float> {
+                Some($float::from_cast(self))
+            }
+            #[inline]
+            fn cast_clipped(self, r: RangeInclusive<$float>) -> Option<$float> {
+                let (start, end) = r.into_inner();
+                let v = $float::from_cast(self);
+                if (v.ge(start) & v.le(end)).all() {
+                    Some(v)
+                } else {
+                    None
+                }
+            }
+            #[inline]
+            fn cast_clamped(self, r: RangeInclusive<$float>) -> $float {
+                let (start, end) = r.into_inner();
+                Real::min(Real::max($float::from_cast(self), start), end)
+            }
+            #[inline]
+            fn cast_clamping(self) -> $float {
+                $float::from_cast(self)
+            }
+        }
+    )* )* )
+}
+
+// the range of $int as floats: the smallest and largest $float within it
+macro_rules! bounds {
+    ($fscalar:ident, $iscalar:ident) => ({
+        let lo = $iscalar::MIN as $fscalar;
+        let hi = $iscalar::MAX as $fscalar;
+        let hi = if hi as i128 > $iscalar::MAX as i128 {
+            $fscalar::from_bits(hi.to_bits() - 1)
+        } else {
+            hi
+        };
+        (lo, hi)
+    })
+}
+
+impl_cast_simd!(
+    f32x4: f32 => [i32x4: i32, u32x4: u32, i16x4: i16, u16x4: u16, i8x4: i8, u8x4: u8];
+    f64x2: f64 => [i64x2: i64, u64x2: u64, i32x2: i32, u32x2: u32, i16x2: i16, u16x2: u16, i8x2: i8, u8x2: u8];
+);
+impl_cast_simd!(
+    f32x8: f32 => [i32x8: i32, u32x8: u32, i16x8: i16, u16x8: u16, i8x8: i8, u8x8: u8];
+    f64x4: f64 => [i64x4: i64, u64x4: u64, i32x4: i32, u32x4: u32, i16x4: i16, u16x4: u16, i8x4: i8, u8x4: u8];
 );
+
+// split input and out into full vectors, handled by `vector`, and a scalar remainder.
+// The vectors run with SSE4.1 enabled, without it everything goes through `scalar`.
+#[inline(always)]
+fn chunked<T, O, V, S>(input: &[T], out: &mut [O], lanes: usize, vector: V, mut scalar: S) -> Option<()>
+    where T: Copy, V: FnMut(&[T], &mut [O]) -> Option<()>, S: FnMut(T) -> Option<O>
+{
+    #[target_feature(enable = "sse4.1")]
+    unsafe fn vectors<T, O, V>(input: &[T], out: &mut [O], lanes: usize, mut vector: V) -> Option<()>
+        where V: FnMut(&[T], &mut [O]) -> Option<()>
+    {
+        for (i, o) in input.chunks(lanes).zip(out.chunks_mut(lanes)) {
+            vector(i, o)?;
+        }
+        Some(())
+    }
+
+    assert_eq!(input.len(), out.len());
+    let sse41 = is_x86_feature_detected!("sse4.1");
+    let full = if sse41 { input.len() - input.len() % lanes } else { 0 };
+    let (input, input_tail) = input.split_at(full);
+    let (out, out_tail) = out.split_at_mut(full);
+    if sse41 {
+        unsafe { vectors(input, out, lanes, vector)?; }
+    }
+    for (&i, o) in input_tail.iter().zip(out_tail.iter_mut()) {
+        *o = scalar(i)?;
+    }
+    Some(())
+}
+
+// Slice casts between f32 and the smaller integers, four values at a time.
+macro_rules! impl_cast_slice_simd {
+    ($float:ident: $fscalar:ident => [$($int:ident: $iscalar:ident),*]) => ( $(
+        impl CastSlice<$iscalar> for $fscalar {
+            #[inline]
+            fn cast_slice(input: &[$fscalar], out: &mut [$iscalar]) -> Option<()> {
+                chunked(input, out, $float::lanes(), |i, o| {
+                    let v: $int = $float::from_slice_unaligned(i).cast()?;
+                    v.write_to_slice_unaligned(o);
+                    Some(())
+                }, |x| x.cast())
+            }
+            #[inline]
+            fn cast_slice_clamping(input: &[$fscalar], out: &mut [$iscalar]) {
+                chunked(input, out, $float::lanes(), |i, o| {
+                    let v: $int = $float::from_slice_unaligned(i).cast_clamping();
+                    v.write_to_slice_unaligned(o);
+                    Some(())
+                }, |x| Some(x.cast_clamping()));
+            }
+            #[inline]
+            fn cast_slice_clamped(input: &[$fscalar], out: &mut [$iscalar], r: RangeInclusive<$iscalar>) {
+                let (start, end) = r.into_inner();
+                let range = $int::splat(start) ..= $int::splat(end);
+                chunked(input, out, $float::lanes(), |i, o| {
+                    $float::from_slice_unaligned(i).cast_clamped(range.clone()).write_to_slice_unaligned(o);
+                    Some(())
+                }, |x| Some(x.cast_clamped(start ..= end)));
+            }
+        }
+        impl CastSlice<$fscalar> for $iscalar {
+            #[inline]
+            fn cast_slice(input: &[$iscalar], out: &mut [$fscalar]) -> Option<()> {
+                Self::cast_slice_clamping(input, out);
+                Some(())
+            }
+            #[inline]
+            fn cast_slice_clamping(input: &[$iscalar], out: &mut [$fscalar]) {
+                chunked(input, out, $float::lanes(), |i, o| {
+                    $float::from_cast($int::from_slice_unaligned(i)).write_to_slice_unaligned(o);
+                    Some(())
+                }, |x| Some(x.cast_clamping()));
+            }
+            #[inline]
+            fn cast_slice_clamped(input: &[$iscalar], out: &mut [$fscalar], r: RangeInclusive<$fscalar>) {
+                let (start, end) = r.into_inner();
+                let range = $float::splat(start) ..= $float::splat(end);
+                chunked(input, out, $float::lanes(), |i, o| {
+                    let v: $float = $int::from_slice_unaligned(i).cast_clamped(range.clone());
+                    v.write_to_slice_unaligned(o);
+                    Some(())
+                }, |x| Some(x.cast_clamped(start ..= end)));
+            }
+        }
+    )* )
+}
+impl_cast_slice_simd!(f32x4: f32 => [i32x4: i32, u32x4: u32, i16x4: i16, u16x4: u16, i8x4: i8, u8x4: u8]);
+
+// Rounding without branches, see `Native::round_even` for nearest-even.
+macro_rules! impl_round_simd {
+    ($($float:ident),*) => ( $(
+        impl Round for $float {
+            #[inline]
+            fn round_with(self, mode: Rounding) -> $float {
+                match mode {
+                    Rounding::NearestEven => self.round_even(),
+                    Rounding::NearestAway => Real::round(self),
+                    Rounding::Floor => Real::floor(self),
+                    Rounding::Ceil => Real::ceil(self),
+                    Rounding::Trunc => Real::trunc(self),
+                }
+            }
+        }
+    )* )
+}
+impl_round_simd!(f32x4, f64x2, f32x8, f64x4);
+
+#[test]
+fn test_select() {
+    let a = f32x4::new(1., 2., 3., 4.);
+    let r = Real::select(a, f32x4::splat(0.), Real::gt(a, f32x4::splat(2.5)));
+    assert_eq!(r, f32x4::new(0., 0., 3., 4.));
+}
+
+#[test]
+fn test_mask_lanes() {
+    let a = f32x4::new(1., -2., 3., f32::NAN);
+    let pos = Real::gt(a, f32x4::splat(0.));
+    let small = Real::lt(a, f32x4::splat(2.));
+    assert_eq!(pos, m32x4::new(true, false, true, false));
+    assert_eq!(Mask::and(pos, small), m32x4::new(true, false, false, false));
+    assert_eq!(Mask::or(pos, small), m32x4::new(true, true, true, false));
+    assert_eq!(Mask::xor(pos, small), m32x4::new(false, true, true, false));
+    assert_eq!(Mask::not(pos), m32x4::new(false, true, false, true));
+    assert!(Mask::any(pos) && !Mask::all(pos) && !Mask::none(pos));
+    assert!(Mask::all(Mask::or(pos, Mask::not(pos))));
+    assert!(Mask::none(Mask::and(pos, Mask::not(pos))));
+    assert_eq!(Mask::count_true(pos), 2);
+    assert_eq!(Mask::count_true(Real::is_nan(a)), 1);
+    assert_eq!(Mask::count_true(<m32x4 as Mask>::splat(true)), 4);
+    assert_eq!(Mask::count_true(<m32x4 as Mask>::splat(false)), 0);
+
+    let b = f64x2::new(-1., 5.);
+    assert_eq!(Mask::count_true(Real::ge(b, f64x2::splat(-1.))), 2);
+    assert_eq!(Mask::count_true(Real::gt(b, f64x2::splat(0.))), 1);
+}
+
+#[test]
+fn test_f32x4() { approx::check_lanes::<f32x4>(1e-6); }
+
+#[test]
+fn test_f64x2() { approx::check_lanes::<f64x2>(1e-14); }
+
+#[test]
+fn test_f32x8() { approx::check_lanes::<f32x8>(1e-6); }
+
+#[test]
+fn test_f64x4() { approx::check_lanes::<f64x4>(1e-14); }
+
+#[test]
+fn test_dispatched() {
+    // the instructions are used directly, so only where the cpu has them
+    if is_x86_feature_detected!("sse4.1") {
+        approx::check_lanes::<Dispatched<f32x4, false>>(1e-6);
+        approx::check_lanes::<Dispatched<f64x2, false>>(1e-14);
+    }
+    if is_x86_feature_detected!("avx2") && is_x86_feature_detected!("fma") {
+        approx::check_lanes::<Dispatched<f32x8, true>>(1e-6);
+        approx::check_lanes::<Dispatched<f64x4, true>>(1e-14);
+    }
+}
+
+#[test]
+fn test_cast_lanes() {
+    let v = f32x4::new(-1.5, 0.7, 255.9, 300.);
+    assert_eq!(Cast::<u8x4>::cast(v), None);
+    assert_eq!(Cast::<u8x4>::cast_clamping(v), u8x4::new(0, 0, 255, 255));
+    assert_eq!(Cast::<i32x4>::cast(v), Some(i32x4::new(-1, 0, 255, 300)));
+    assert_eq!(v.cast_clamped(i32x4::splat(0) ..= i32x4::splat(100)), i32x4::new(0, 0, 100, 100));
+    assert_eq!(Cast::<i32x4>::cast_clamping(f32x4::splat(3e9)), i32x4::splat(i32::MAX));
+}
+
+#[test]
+fn test_round_lanes() {
+    use cast::CastRounded;
+    let v = f32x4::new(-2.5, 0.5, 1.5, 2.7);
+    assert_eq!(v.round_with(Rounding::NearestEven), f32x4::new(-2., 0., 2., 3.));
+    assert_eq!(v.round_with(Rounding::NearestAway), f32x4::new(-3., 1., 2., 3.));
+    assert_eq!(v.cast_rounded(Rounding::Floor), Some(i32x4::new(-3, 0, 1, 2)));
+    assert_eq!(CastRounded::<u8x4>::cast_rounded_clamping(v, Rounding::Ceil), u8x4::new(0, 1, 2, 3));
+}
+
+#[test]
+fn test_cast_slice_lanes() {
+    use cast::{cast_slice, cast_slice_clamping, cast_slice_clamped};
+    // 4 full vectors and a remainder of 3
+    let input: Vec<i16> = (-9 .. 10).map(|i| i * 3001).collect();
+    let mut out = vec![0f32; input.len()];
+    assert_eq!(cast_slice(&input, &mut out), Some(()));
+    assert!(input.iter().zip(&out).all(|(&i, &o)| i as f32 == o));
+
+    let mut back = vec![0u8; out.len()];
+    assert_eq!(cast_slice(&out, &mut back), None);
+    cast_slice_clamping(&out, &mut back);
+    assert!(out.iter().zip(&back).all(|(&o, &b)| Cast::<u8>::cast_clamping(o) == b));
+    cast_slice_clamped(&out, &mut back, 10 ..= 20);
+    assert!(out.iter().zip(&back).all(|(&o, &b)| o.cast_clamped(10u8 ..= 20) == b));
+}
@@ -0,0 +1,261 @@
+// Property tests for `Cast`: every scalar impl is compared against a reference
+// model that works on exact wide integers, on random values biased towards
+// the boundaries of the involved types.
+extern crate math_traits;
+extern crate rand;
+
+use math_traits::Cast;
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use std::cmp::Ordering;
+
+const ROUNDS: usize = 2000;
+const TWO_128: f64 = 340282366920938463463374607431768211456.;
+
+/// An exact integer, or +-inf. Floats are truncated towards zero.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Num {
+    NegInf,
+    // u128::MAX - magnitude, so larger magnitudes order first
+    Neg(u128),
+    NonNeg(u128),
+    PosInf,
+}
+
+trait Model: Copy {
+    /// None for NaN
+    fn model(self) -> Option<Num>;
+
+    /// false for infinities and NaN
+    fn finite(self) -> bool {
+        true
+    }
+}
+
+macro_rules! model_unsigned {
+    ($($t:ident),*) => ( $(
+        impl Model for $t {
+            fn model(self) -> Option<Num> {
+                Some(Num::NonNeg(self as u128))
+            }
+        }
+    )* )
+}
+macro_rules! model_signed {
+    ($($t:ident),*) => ( $(
+        impl Model for $t {
+            fn model(self) -> Option<Num> {
+                if self < 0 {
+                    Some(Num::Neg(u128::MAX - (self as i128).wrapping_neg() as u128))
+                } else {
+                    Some(Num::NonNeg(self as u128))
+                }
+            }
+        }
+    )* )
+}
+macro_rules! model_float {
+    ($($t:ident),*) => ( $(
+        impl Model for $t {
+            fn model(self) -> Option<Num> {
+                let t = self.trunc();
+                if t != t {
+                    None
+                } else if t as f64 >= TWO_128 {
+                    Some(Num::PosInf)
+                } else if t as f64 <= -TWO_128 {
+                    Some(Num::NegInf)
+                } else if t < 0. {
+                    Some(Num::Neg(u128::MAX - (-t) as u128))
+                } else {
+                    Some(Num::NonNeg(t as u128))
+                }
+            }
+            fn finite(self) -> bool {
+                self.is_finite()
+            }
+        }
+    )* )
+}
+model_unsigned!(u8, u16, u32, u64, u128, usize);
+model_signed!(i8, i16, i32, i64, i128, isize);
+model_float!(f32, f64);
+
+trait Sample: Copy {
+    fn sample(rng: &mut StdRng) -> Self;
+}
+
+// boundaries of every integer type, as i128 (u128::MAX is added separately)
+const EDGES: &[i128] = &[
+    0, 1, 2, 100,
+    127, 128, 255, 256,
+    32767, 32768, 65535, 65536,
+    2147483647, 2147483648, 4294967295, 4294967296,
+    9223372036854775807, 9223372036854775808, 18446744073709551615, 18446744073709551616,
+    170141183460469231731687303715884105727,
+];
+
+macro_rules! sample_int {
+    ($($t:ident),*) => ( $(
+        impl Sample for $t {
+            fn sample(rng: &mut StdRng) -> $t {
+                match rng.gen_range(0, 4) {
+                    0 => rng.gen(),
+                    1 => {
+                        let e = EDGES[rng.gen_range(0, EDGES.len())];
+                        let e = e.wrapping_add(rng.gen_range(-2, 3));
+                        let e = if rng.gen() { e } else { e.wrapping_neg() };
+                        e as $t
+                    }
+                    2 => [$t::MIN, $t::MAX][rng.gen_range(0, 2)],
+                    _ => rng.gen_range(0, 300) as $t,
+                }
+            }
+        }
+    )* )
+}
+macro_rules! sample_float {
+    ($($t:ident: $bits:ident),*) => ( $(
+        impl Sample for $t {
+            fn sample(rng: &mut StdRng) -> $t {
+                match rng.gen_range(0, 5) {
+                    // anything, including NaN and inf
+                    0 => $t::from_bits(rng.gen::<$bits>()),
+                    1 => {
+                        // near the boundaries, including the neighbouring floats
+                        let e = EDGES[rng.gen_range(0, EDGES.len())] as $t;
+                        let e = match rng.gen_range(0, 3) {
+                            0 => e,
+                            1 => $t::from_bits(e.to_bits() + 1),
+                            _ => $t::from_bits(e.to_bits().saturating_sub(1)),
+                        };
+                        if rng.gen() { e } else { -e }
+                    }
+                    2 => rng.gen_range(-300., 300.),
+                    3 => rng.gen_range(-2, 3) as $t + [0., 0.5, 0.25, 0.75][rng.gen_range(0, 4)],
+                    _ => [$t::INFINITY, $t::NEG_INFINITY, $t::NAN, -0., $t::MAX, $t::MIN][rng.gen_range(0, 6)],
+                }
+            }
+        }
+    )* )
+}
+sample_int!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+sample_float!(f32: u32, f64: u64);
+
+fn clamp(v: Num, lo: Num, hi: Num) -> Num {
+    if v < lo { lo } else if v > hi { hi } else { v }
+}
+
+// integer targets: all four methods follow from the model
+macro_rules! check_int {
+    ($rng:expr, [$($src:ident),*] => $dst:tt) => ( $( check_int!(@ $rng, $src => $dst); )* );
+    (@ $rng:expr, $src:ident => [$($dst:ident),*]) => ( $(
+        let lo = $dst::MIN.model().unwrap();
+        let hi = $dst::MAX.model().unwrap();
+        for _ in 0 .. ROUNDS {
+            let x = $src::sample($rng);
+            let m = x.model();
+            let ctx = || format!("{}({:?}) -> {}", stringify!($src), x, stringify!($dst));
+
+            let expected = m.filter(|&m| m >= lo && m <= hi);
+            assert_eq!(Cast::<$dst>::cast(x).map(Model::model), expected.map(Some), "cast {}", ctx());
+
+            let clamping = m.map_or(0.model().unwrap(), |m| clamp(m, lo, hi));
+            assert_eq!(Cast::<$dst>::cast_clamping(x).model(), Some(clamping), "cast_clamping {}", ctx());
+
+            let (a, b) = ($dst::sample($rng), $dst::sample($rng));
+            let (start, end) = match rng_order($rng) {
+                Ordering::Less => (a, a),
+                _ => if a <= b { (a, b) } else { (b, a) }
+            };
+            let (s, e) = (start.model().unwrap(), end.model().unwrap());
+
+            let clipped = m.filter(|&m| m >= s && m <= e);
+            assert_eq!(x.cast_clipped(start ..= end).map(Model::model), clipped.map(Some),
+                "cast_clipped({:?} ..= {:?}) {}", start, end, ctx());
+
+            let clamped = clamp(clamping, s, e);
+            assert_eq!(x.cast_clamped(start ..= end).model(), Some(clamped),
+                "cast_clamped({:?} ..= {:?}) {}", start, end, ctx());
+
+            // an empty range never matches
+            if start < end {
+                assert_eq!(x.cast_clipped(end ..= start), None, "empty range {}", ctx());
+            }
+        }
+    )* );
+}
+
+// float targets: finite values within MIN ..= MAX cast like `as`, everything else is None.
+// cast_clamping clamps to that range, including the infinities, and turns NaN into 0.
+// Every float of the targets is integral beyond 2^24, so the truncating model compares
+// the bounds exactly.
+macro_rules! check_float {
+    ($rng:expr, [$($src:ident),*] => $dst:tt) => ( $( check_float!(@ $rng, $src => $dst); )* );
+    (@ $rng:expr, $src:ident => [$($dst:ident),*]) => ( $(
+        let lo = $dst::MIN.model().unwrap();
+        let hi = $dst::MAX.model().unwrap();
+        for _ in 0 .. ROUNDS {
+            let x = $src::sample($rng);
+            let m = x.model();
+            let ctx = || format!("{}({:?}) -> {}", stringify!($src), x, stringify!($dst));
+
+            let expected = m.filter(|&m| x.finite() && m >= lo && m <= hi).map(|_| x as $dst);
+            assert_eq!(Cast::<$dst>::cast(x).map($dst::to_bits), expected.map($dst::to_bits), "cast {}", ctx());
+
+            let clamping = match m {
+                None => 0.,
+                Some(m) if m < lo || (!x.finite() && m < Num::NonNeg(0)) => $dst::MIN,
+                Some(m) if m > hi || !x.finite() => $dst::MAX,
+                Some(_) => x as $dst,
+            };
+            assert_eq!(Cast::<$dst>::cast_clamping(x).to_bits(), clamping.to_bits(), "cast_clamping {}", ctx());
+
+            let (a, b) = ($dst::sample($rng), $dst::sample($rng));
+            if !a.finite() || !b.finite() {
+                continue;
+            }
+            let (start, end) = if a <= b { (a, b) } else { (b, a) };
+
+            let clipped = expected.filter(|&c| c >= start && c <= end);
+            assert_eq!(x.cast_clipped(start ..= end).map($dst::to_bits), clipped.map($dst::to_bits),
+                "cast_clipped({:?} ..= {:?}) {}", start, end, ctx());
+
+            let clamped = if clamping < start { start } else if clamping > end { end } else { clamping };
+            assert_eq!(x.cast_clamped(start ..= end).to_bits(), clamped.to_bits(),
+                "cast_clamped({:?} ..= {:?}) {}", start, end, ctx());
+        }
+    )* );
+}
+
+fn rng_order(rng: &mut StdRng) -> Ordering {
+    if rng.gen_range(0, 8) == 0 { Ordering::Less } else { Ordering::Greater }
+}
+
+#[test]
+fn int_to_int() {
+    let rng = &mut StdRng::seed_from_u64(1);
+    check_int!(rng, [u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize]
+        => [u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize]);
+}
+
+#[test]
+fn float_to_int() {
+    let rng = &mut StdRng::seed_from_u64(2);
+    check_int!(rng, [f32, f64] => [u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize]);
+}
+
+#[test]
+fn to_float() {
+    let rng = &mut StdRng::seed_from_u64(3);
+    check_float!(rng, [u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64] => [f32, f64]);
+
+    // u128 above f32::MAX is too rare to be sampled
+    let max = f32::MAX as u128;
+    assert_eq!(Cast::<f32>::cast(max), Some(f32::MAX));
+    assert_eq!(Cast::<f32>::cast(max + 1), None);
+    assert_eq!(Cast::<f32>::cast_clamping(u128::MAX), f32::MAX);
+    assert_eq!(Cast::<f32>::cast_clamping(f64::INFINITY), f32::MAX);
+    assert_eq!(Cast::<f32>::cast_clamping(f64::NEG_INFINITY), f32::MIN);
+    assert_eq!(Cast::<f32>::cast_clamping(f64::NAN), 0.);
+}
@@ -0,0 +1,52 @@
+#![cfg(feature="derive")]
+extern crate math_traits;
+
+use math_traits::Cast;
+
+#[derive(Cast, Copy, Clone, Debug, PartialEq)]
+struct Rgb<T> {
+    r: T,
+    g: T,
+    pub b: T,
+}
+
+#[derive(Cast, Debug, PartialEq)]
+struct Point3<T>(T, T, T);
+
+#[derive(Cast, Debug, PartialEq)]
+struct Tagged<T: Copy, U> {
+    id: u8,
+    value: T,
+    pair: [U; 2],
+}
+
+#[derive(Cast, Debug, PartialEq)]
+struct Unit;
+
+#[test]
+fn test_derive_named() {
+    let c = Rgb { r: 1.5f32, g: 300., b: -2. };
+    assert_eq!(c.cast(), None::<Rgb<u8>>);
+    assert_eq!(Cast::<Rgb<u8>>::cast_clamping(c), Rgb { r: 1, g: 255, b: 0 });
+    assert_eq!(Cast::<Rgb<i32>>::cast(c), Some(Rgb { r: 1, g: 300, b: -2 }));
+
+    let lo = Rgb { r: 0u8, g: 0, b: 0 };
+    let hi = Rgb { r: 1u8, g: 100, b: 10 };
+    assert_eq!(c.cast_clamped(lo ..= hi), Rgb { r: 1, g: 100, b: 0 });
+    assert_eq!(c.cast_clipped(lo ..= hi), None);
+    assert_eq!(Rgb { r: 1f32, g: 2., b: 3. }.cast_clipped(lo ..= hi), Some(Rgb { r: 1, g: 2, b: 3 }));
+}
+
+#[test]
+fn test_derive_tuple_struct() {
+    assert_eq!(Point3(1i64, -1, 2).cast(), None::<Point3<u16>>);
+    assert_eq!(Point3(1i64, 5, 2).cast(), Some(Point3(1u16, 5, 2)));
+    assert_eq!(Cast::<Point3<f64>>::cast_clamping(Point3(1i32, 5, 2)), Point3(1., 5., 2.));
+}
+
+#[test]
+fn test_derive_mixed() {
+    let t = Tagged { id: 7, value: 2.5f64, pair: [1u32, 70000] };
+    assert_eq!(Cast::<Tagged<i8, u16>>::cast_clamping(t), Tagged { id: 7, value: 2, pair: [1, 65535] });
+    assert_eq!(Cast::<Unit>::cast(Unit), Some(Unit));
+}